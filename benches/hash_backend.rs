@@ -0,0 +1,63 @@
+//! Compare the C and Rust hash primitives (the `rust-keccak` and
+//! `rust-sha2` features) on the "f" parameter sets.
+//!
+//! The backend is selected at build time: benchmark the C primitives first,
+//! then compare the Rust ones against them.
+//!
+//! ```sh
+//! cargo bench --bench hash_backend -- --save-baseline c
+//! cargo bench --features rust-keccak,rust-sha2 --bench hash_backend -- --baseline c
+//! ```
+
+// Nothing is benchmarked without an "f" parameter set
+#![cfg_attr(not(slhdsa_fast_sets), allow(dead_code, unused_imports))]
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use slhdsa_c_rs::*;
+
+const MSG: &[u8] = b"slhdsa-c-rs hash backend benchmark";
+
+fn bench_parameter_set<P: ParameterSet>(c: &mut Criterion) {
+    let n = P::VERIFYING_KEY_LEN / 2;
+    let seeds = [0x42u8; 3 * 32];
+    let (sk, vk) =
+        keygen_from_seeds::<P>(&seeds[..n], &seeds[n..2 * n], &seeds[2 * n..3 * n]).unwrap();
+    let sig = sk.try_sign_with_ctx(MSG, b"").unwrap();
+
+    let mut group = c.benchmark_group(P::NAME);
+    group.sample_size(10);
+    group.bench_function("keygen", |b| {
+        b.iter(|| {
+            keygen_from_seeds::<P>(&seeds[..n], &seeds[n..2 * n], &seeds[2 * n..3 * n]).unwrap()
+        });
+    });
+    group.bench_function("sign", |b| {
+        b.iter(|| sk.try_sign_with_ctx(black_box(MSG), b"").unwrap());
+    });
+    group.bench_function("verify", |b| {
+        b.iter(|| vk.verify_with_ctx(black_box(MSG), b"", &sig).unwrap());
+    });
+    group.finish();
+}
+
+fn bench_hash_backends(c: &mut Criterion) {
+    println!("{}", build_info::BUILD_INFO);
+    #[cfg(feature = "sha2-128f")]
+    bench_parameter_set::<SLH_DSA_SHA2_128f>(c);
+    #[cfg(feature = "sha2-192f")]
+    bench_parameter_set::<SLH_DSA_SHA2_192f>(c);
+    #[cfg(feature = "sha2-256f")]
+    bench_parameter_set::<SLH_DSA_SHA2_256f>(c);
+    #[cfg(feature = "shake-128f")]
+    bench_parameter_set::<SLH_DSA_SHAKE_128f>(c);
+    #[cfg(feature = "shake-192f")]
+    bench_parameter_set::<SLH_DSA_SHAKE_192f>(c);
+    #[cfg(feature = "shake-256f")]
+    bench_parameter_set::<SLH_DSA_SHAKE_256f>(c);
+    let _ = c;
+}
+
+criterion_group!(benches, bench_hash_backends);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+//! Compare prepared keys (the `prepared` feature) with plain keys, on every
+//! enabled parameter set.
+//!
+//! ```sh
+//! cargo bench --features prepared --bench prepared_key
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use slhdsa_c_rs::*;
+
+const MSG: &[u8] = b"slhdsa-c-rs prepared key benchmark";
+
+fn bench_parameter_set<P: ParameterSet>(c: &mut Criterion) {
+    let n = P::VERIFYING_KEY_LEN / 2;
+    let seeds = [0x42u8; 3 * 32];
+    let (sk, vk) =
+        keygen_from_seeds::<P>(&seeds[..n], &seeds[n..2 * n], &seeds[2 * n..3 * n]).unwrap();
+    let sig = sk.try_sign_with_ctx(MSG, b"").unwrap();
+    let prepared_sk = PreparedSigningKey::new(sk.clone());
+    let prepared_vk = PreparedVerifyingKey::new(vk.clone());
+    assert!(prepared_sk.is_prepared() && prepared_vk.is_prepared());
+
+    let mut group = c.benchmark_group(P::NAME);
+    group.sample_size(10);
+    group.bench_function("sign", |b| {
+        b.iter(|| sk.try_sign_with_ctx(black_box(MSG), b"").unwrap());
+    });
+    group.bench_function("sign prepared", |b| {
+        b.iter(|| prepared_sk.try_sign_with_ctx(black_box(MSG), b"").unwrap());
+    });
+    group.bench_function("verify", |b| {
+        b.iter(|| vk.verify_with_ctx(black_box(MSG), b"", &sig).unwrap());
+    });
+    group.bench_function("verify prepared", |b| {
+        b.iter(|| {
+            prepared_vk
+                .verify_with_ctx(black_box(MSG), b"", &sig)
+                .unwrap()
+        });
+    });
+    group.finish();
+}
+
+fn bench_prepared_keys(c: &mut Criterion) {
+    println!("{}", build_info::BUILD_INFO);
+    #[cfg(feature = "sha2-128s")]
+    bench_parameter_set::<SLH_DSA_SHA2_128s>(c);
+    #[cfg(feature = "sha2-128f")]
+    bench_parameter_set::<SLH_DSA_SHA2_128f>(c);
+    #[cfg(feature = "sha2-192s")]
+    bench_parameter_set::<SLH_DSA_SHA2_192s>(c);
+    #[cfg(feature = "sha2-192f")]
+    bench_parameter_set::<SLH_DSA_SHA2_192f>(c);
+    #[cfg(feature = "sha2-256s")]
+    bench_parameter_set::<SLH_DSA_SHA2_256s>(c);
+    #[cfg(feature = "sha2-256f")]
+    bench_parameter_set::<SLH_DSA_SHA2_256f>(c);
+    #[cfg(feature = "shake-128s")]
+    bench_parameter_set::<SLH_DSA_SHAKE_128s>(c);
+    #[cfg(feature = "shake-128f")]
+    bench_parameter_set::<SLH_DSA_SHAKE_128f>(c);
+    #[cfg(feature = "shake-192s")]
+    bench_parameter_set::<SLH_DSA_SHAKE_192s>(c);
+    #[cfg(feature = "shake-192f")]
+    bench_parameter_set::<SLH_DSA_SHAKE_192f>(c);
+    #[cfg(feature = "shake-256s")]
+    bench_parameter_set::<SLH_DSA_SHAKE_256s>(c);
+    #[cfg(feature = "shake-256f")]
+    bench_parameter_set::<SLH_DSA_SHAKE_256f>(c);
+    let _ = c;
+}
+
+criterion_group!(benches, bench_prepared_keys);
+criterion_main!(benches);
@@ -1,12 +1,264 @@
+#[cfg(not(feature = "system"))]
 use glob::glob;
+use std::path::{Path, PathBuf};
 
+#[cfg(not(feature = "system"))]
 const SLHDSA_C_PATH: &str = "./slhdsa-c/";
 
-fn compile_c_sources() {
-    let include_path = std::path::PathBuf::from(SLHDSA_C_PATH);
+/// C helpers of this crate, built along with `slhdsa-c`
+#[cfg(not(feature = "system"))]
+const CSRC_PATH: &str = "./csrc/";
 
+/// Overrides [`SLHDSA_C_PATH`], e.g. to build a patched copy of `slhdsa-c`
+#[cfg(not(feature = "system"))]
+const SLHDSA_C_SRC_DIR_ENV: &str = "SLHDSA_C_SRC_DIR";
+
+/// Cargo features enabling the SHA2-based parameter sets
+const SHA2_PARAMETER_SETS: [&str; 6] = [
+    "sha2-128s",
+    "sha2-128f",
+    "sha2-192s",
+    "sha2-192f",
+    "sha2-256s",
+    "sha2-256f",
+];
+
+/// Cargo features enabling the SHAKE-based parameter sets
+const SHAKE_PARAMETER_SETS: [&str; 6] = [
+    "shake-128s",
+    "shake-128f",
+    "shake-192s",
+    "shake-192f",
+    "shake-256s",
+    "shake-256f",
+];
+
+fn feature_enabled(feature: &str) -> bool {
+    let var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+    std::env::var_os(var).is_some()
+}
+
+/// Which C sources to compile, depending on the enabled parameter sets
+#[cfg(not(feature = "system"))]
+struct SourceSelection {
+    sha2: bool,
+    shake: bool,
+    prehash: bool,
+}
+
+#[cfg(not(feature = "system"))]
+impl SourceSelection {
+    fn from_features() -> Self {
+        let sha2 = SHA2_PARAMETER_SETS.iter().any(|f| feature_enabled(f));
+        let shake = SHAKE_PARAMETER_SETS.iter().any(|f| feature_enabled(f));
+        if !sha2 && !shake {
+            println!("cargo::warning=No SLH-DSA parameter set feature is enabled");
+        }
+
+        // HashSLH-DSA needs the SHA2 and SHAKE hash functions regardless of
+        // the parameter set. Keep it unless a whole family is disabled, and
+        // the ACVP runner (its only user in the wrapper) is not enabled.
+        let prehash = (sha2 && shake) || feature_enabled("acvp");
+
+        Self {
+            sha2,
+            shake,
+            prehash,
+        }
+    }
+
+    fn all_enabled() -> bool {
+        SHA2_PARAMETER_SETS
+            .iter()
+            .chain(SHAKE_PARAMETER_SETS.iter())
+            .all(|f| feature_enabled(f))
+    }
+
+    /// Whether `file_name` (from the top-level `slhdsa-c` directory) must be
+    /// compiled
+    fn includes(&self, file_name: &str) -> bool {
+        match file_name {
+            // Parameter tables for each hash family
+            "slh_sha2.c" => self.sha2,
+            "slh_shake.c" => self.shake,
+            "slh_prehash.c" => self.prehash,
+            // Hash function implementations
+            f if f.starts_with("sha2_") => self.sha2 || self.prehash,
+            f if f.starts_with("sha3_") || f.starts_with("keccak") => self.shake || self.prehash,
+            _ => true,
+        }
+    }
+}
+
+/// Comma-separated list of sanitizers to build the C code with (e.g.,
+/// `address,undefined`), in addition to the ones of the `sanitize` feature
+const SLHDSA_C_SANITIZE_ENV: &str = "SLHDSA_C_SANITIZE";
+
+/// Enables the hardening flags, like the `hardening` feature
+const SLHDSA_C_HARDENING_ENV: &str = "SLHDSA_C_HARDENING";
+
+/// Whether the target has no operating system (e.g., `thumbv7em-none-eabihf`)
+fn bare_metal() -> bool {
+    std::env::var("CARGO_CFG_TARGET_OS").is_ok_and(|os| os == "none")
+}
+
+/// Instrumentation and hardening of the C library
+struct BuildMode {
+    /// Sanitizers the C code is instrumented with
+    sanitizers: Vec<String>,
+    /// Whether the Rust code is instrumented as well (e.g., by `cargo fuzz`),
+    /// in which case rustc links the sanitizer runtime
+    #[cfg_attr(feature = "system", allow(dead_code))]
+    rust_sanitized: bool,
+    hardening: bool,
+}
+
+impl BuildMode {
+    fn from_env() -> Self {
+        println!("cargo:rerun-if-env-changed={SLHDSA_C_SANITIZE_ENV}");
+        println!("cargo:rerun-if-env-changed={SLHDSA_C_HARDENING_ENV}");
+
+        let mut sanitizers: Vec<String> = Vec::new();
+        let mut add = |list: &str| {
+            for s in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                if !sanitizers.iter().any(|x| x == s) {
+                    sanitizers.push(s.to_owned());
+                }
+            }
+        };
+        if feature_enabled("sanitize") {
+            add("address,undefined");
+        }
+        if let Ok(list) = std::env::var(SLHDSA_C_SANITIZE_ENV) {
+            add(&list);
+        }
+
+        // When the Rust code is built with `-Zsanitizer=address` (e.g., by
+        // `cargo fuzz`), instrument the C code as well: most of the attack
+        // surface lives there.
+        let rust_sanitized =
+            std::env::var("CARGO_CFG_SANITIZE").is_ok_and(|s| s.split(',').any(|s| s == "address"));
+        if rust_sanitized {
+            add("address");
+        }
+
+        let mut hardening = feature_enabled("hardening")
+            || std::env::var_os(SLHDSA_C_HARDENING_ENV).is_some_and(|v| v != "0");
+
+        // Sanitizer runtimes, the stack protector and `_FORTIFY_SOURCE` all
+        // rely on an operating system and a libc
+        if bare_metal() {
+            if !sanitizers.is_empty() {
+                println!("cargo::error=Sanitizers are not supported on bare-metal targets");
+                std::process::exit(1);
+            }
+            if hardening {
+                println!("cargo::warning=Hardening flags are ignored on bare-metal targets");
+                hardening = false;
+            }
+        }
+
+        Self {
+            sanitizers,
+            rust_sanitized,
+            hardening,
+        }
+    }
+
+    /// Add the optimization, instrumentation and hardening flags to `build`
+    #[cfg(not(feature = "system"))]
+    fn apply(&self, build: &mut cc::Build) {
+        if self.sanitizers.is_empty() {
+            build.flag("-O3").flag("-fomit-frame-pointer");
+        } else {
+            // Keep stack traces usable, and make UBSan findings fatal so
+            // that they fail the tests
+            build
+                .flag("-O1")
+                .flag("-g")
+                .flag("-fno-omit-frame-pointer")
+                .flag(format!("-fsanitize={}", self.sanitizers.join(",")))
+                .flag("-fno-sanitize-recover=all");
+        }
+
+        if self.hardening {
+            build
+                .flag("-fstack-protector-strong")
+                .flag_if_supported("-fstack-clash-protection")
+                // Some toolchains already define it, possibly to another level
+                .flag("-U_FORTIFY_SOURCE")
+                .flag("-D_FORTIFY_SOURCE=2");
+
+            // Control-flow protection: CET on x86, PAC/BTI on AArch64
+            match std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() {
+                Ok("x86" | "x86_64") => {
+                    build.flag_if_supported("-fcf-protection=full");
+                }
+                Ok("aarch64") => {
+                    build.flag_if_supported("-mbranch-protection=standard");
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Link the sanitizer runtimes into the tests, examples and benchmarks of
+    /// this crate, unless rustc already does.
+    ///
+    /// Dependent crates must pass `-Clink-arg=-fsanitize=...` themselves.
+    #[cfg(not(feature = "system"))]
+    fn link(&self) {
+        if !self.sanitizers.is_empty() && !self.rust_sanitized {
+            println!(
+                "cargo:rustc-link-arg=-fsanitize={}",
+                self.sanitizers.join(",")
+            );
+        }
+    }
+
+    /// Expose the configuration to `slhdsa_c_rs::build_info`
+    fn report(&self, source: &str) {
+        println!("cargo:rustc-env=SLHDSA_C_RS_SOURCE={source}");
+        println!(
+            "cargo:rustc-env=SLHDSA_C_RS_SANITIZERS={}",
+            self.sanitizers.join(",")
+        );
+        println!(
+            "cargo:rustc-env=SLHDSA_C_RS_HARDENING={}",
+            u8::from(self.hardening)
+        );
+        println!(
+            "cargo:rustc-env=SLHDSA_C_RS_RUST_KECCAK={}",
+            u8::from(feature_enabled("rust-keccak"))
+        );
+        println!(
+            "cargo:rustc-env=SLHDSA_C_RS_RUST_SHA2={}",
+            u8::from(feature_enabled("rust-sha2"))
+        );
+    }
+}
+
+/// The directory of the `slhdsa-c` sources, and whether it was overridden
+#[cfg(not(feature = "system"))]
+fn source_dir() -> (PathBuf, bool) {
+    println!("cargo:rerun-if-env-changed={SLHDSA_C_SRC_DIR_ENV}");
+    match std::env::var_os(SLHDSA_C_SRC_DIR_ENV) {
+        Some(dir) => (PathBuf::from(dir), true),
+        None => (PathBuf::from(SLHDSA_C_PATH), false),
+    }
+}
+
+/// Size of the buffers holding the `slh_param_t` of custom parameter sets
+/// (`PARAM_STORAGE_LEN` in `src/wrapper/custom.rs`), checked against
+/// `sizeof(slh_param_t)` when compiling `csrc/custom_params.c`
+#[cfg(not(feature = "system"))]
+const PARAM_STORAGE_LEN: usize = 256;
+
+/// Compile the C sources, and return whether HashSLH-DSA is compiled in
+#[cfg(not(feature = "system"))]
+fn compile_c_sources(include_path: &Path, mode: &BuildMode) -> bool {
     let mut build = cc::Build::new();
-    build.include(&include_path);
+    build.include(include_path);
     build
         .flag("-Wall")
         .flag("-Wextra")
@@ -19,20 +271,99 @@ fn compile_c_sources() {
         .flag("-Wredundant-decls")
         .flag("-Wno-long-long")
         .flag("-Wno-unknown-pragmas")
-        .flag("-O3")
-        .flag("-fomit-frame-pointer")
         .flag("-std=c99")
         .flag("-pedantic");
+    mode.apply(&mut build);
+
+    // slhdsa-c keeps keygen, signing and verification in the same
+    // translation unit, so we cannot simply skip compiling the signing
+    // objects. For verify-only builds, place each function in its own
+    // section instead, so the linker can drop the unreferenced keygen/sign
+    // code paths.
+    //
+    // Likewise, the parameter tables of a hash family live in the same
+    // translation unit: when only some of the parameter sets are enabled,
+    // this lets the linker drop the unreferenced tables.
+    //
+    // On bare-metal targets, flash is usually scarce: always do it.
+    if std::env::var_os("CARGO_FEATURE_SIGN").is_none()
+        || !SourceSelection::all_enabled()
+        || bare_metal()
+    {
+        build.flag("-ffunction-sections").flag("-fdata-sections");
+    }
 
-    let pattern = include_path.clone().join("*.c");
+    // The C code only needs the freestanding headers and `string.h`: make
+    // sure no other libc facility is assumed. The `mem*` functions are
+    // provided by `compiler_builtins` on these targets.
+    if bare_metal() {
+        build.flag("-ffreestanding");
+    }
+
+    // `cargo fuzz` builds with `--cfg fuzzing`: add coverage instrumentation
+    // to the C code, so libFuzzer can also be guided by it (requires clang).
+    if std::env::var_os("CARGO_CFG_FUZZING").is_some() {
+        build.flag_if_supported("-fsanitize=fuzzer-no-link");
+    }
+
+    // With `rust-keccak`, the C permutation is compiled separately under
+    // another name, and the C code calls the Rust one instead (see
+    // `src/hash_backend.rs`), whose tests compare it with the C one. Both
+    // get names prefixed by the crate, which cannot clash with other
+    // definitions of the common `keccak_f1600()` in the final binary.
+    let rust_keccak = feature_enabled("rust-keccak");
+    let mut keccak_build = build.clone();
+    let mut keccak_found = false;
+    if rust_keccak {
+        build.define(KECCAK_F1600, Some(KECCAK_F1600_RUST));
+    }
+
+    // With `rust-sha2`, likewise for the SHA-2 compression functions, whose
+    // definitions are renamed in a copy of the files defining them
+    let rust_sha2 = feature_enabled("rust-sha2");
+    let mut sha2_found = 0;
+    if rust_sha2 {
+        for (_, function) in SHA2_COMPRESS {
+            build.define(function, Some(rust_name(function).as_str()));
+        }
+    }
+
+    let pattern = include_path.join("*.c");
     let pattern = pattern.to_str().expect("Path not valid UTF-8");
 
+    let selection = SourceSelection::from_features();
     let matched = glob(pattern).expect("Failed to read glob pattern");
     let mut count: usize = 0;
     for entry in matched {
         match entry {
             Ok(path) => {
                 println!("cargo:rerun-if-changed={}", path.display());
+                let file_name = path
+                    .file_name()
+                    .and_then(|f| f.to_str())
+                    .unwrap_or_default();
+                if !selection.includes(file_name) {
+                    continue;
+                }
+                if rust_keccak && defines_keccak_f1600(&path) {
+                    keccak_build.file(path);
+                    keccak_found = true;
+                    continue;
+                }
+                if let Some((_, function)) = SHA2_COMPRESS
+                    .iter()
+                    .find(|(file, _)| rust_sha2 && *file == file_name)
+                {
+                    build.file(rename_definition(&path, function, &c_name(function)));
+                    sha2_found += 1;
+                    count += 1;
+                    continue;
+                }
+                if file_name == SLH_DSA_C && feature_enabled("custom") {
+                    build.file(with_experimental_bounds(&path));
+                    count += 1;
+                    continue;
+                }
                 build.file(path);
                 count += 1;
             }
@@ -51,32 +382,939 @@ fn compile_c_sources() {
         std::process::exit(1);
     }
 
+    // Custom parameter sets (see `src/wrapper/custom.rs`). `custom_params.c`
+    // fails to compile if `slh_param_t` does not fit in the Rust storage.
+    if feature_enabled("custom") {
+        let custom_params = Path::new(CSRC_PATH).join("custom_params.c");
+        println!("cargo:rerun-if-changed={}", custom_params.display());
+        build
+            .define(
+                "SLHDSA_RS_PARAM_STORAGE_LEN",
+                Some(PARAM_STORAGE_LEN.to_string().as_str()),
+            )
+            .file(custom_params);
+        println!("cargo:rustc-env=SLHDSA_C_RS_PARAM_STORAGE_LEN={PARAM_STORAGE_LEN}");
+    }
+
+    // Signing from a digest computed in Rust (see `src/wrapper/stream.rs`)
+    if feature_enabled("stream") {
+        let digest_sign = Path::new(CSRC_PATH).join("digest_sign.c");
+        println!("cargo:rerun-if-changed={}", digest_sign.display());
+        build.file(digest_sign);
+    }
+
+    // Keys prepared for repeated operations (see `src/wrapper/prepared.rs`)
+    if feature_enabled("prepared") {
+        let prepared_key = Path::new(CSRC_PATH).join("prepared_key.c");
+        println!("cargo:rerun-if-changed={}", prepared_key.display());
+        build.file(prepared_key);
+    }
+
+    // Hash families whose parameter tables are linked in
+    for (family, enabled) in [("sha2", selection.sha2), ("shake", selection.shake)] {
+        if enabled {
+            println!("cargo::rustc-cfg=slhdsa_c_{family}");
+        }
+    }
+
     build.compile("slhdsa-c"); // creates libslhdsa-c.a
+    if keccak_found {
+        keccak_build
+            .define(KECCAK_F1600, Some(KECCAK_F1600_C))
+            .compile("slhdsa-c-keccak"); // creates libslhdsa-c-keccak.a
+        println!("cargo::rustc-cfg=slhdsa_c_keccak");
+    } else if rust_keccak && (selection.shake || selection.prehash) {
+        println!(
+            "cargo::error=`rust-keccak`: no source file of {} defines `{KECCAK_F1600}()` on its own",
+            include_path.display()
+        );
+        std::process::exit(1);
+    }
+    if sha2_found == SHA2_COMPRESS.len() {
+        println!("cargo::rustc-cfg=slhdsa_c_sha2_compress");
+    } else if rust_sha2 && (selection.sha2 || selection.prehash) {
+        println!(
+            "cargo::error=`rust-sha2`: {} does not hold {:?}",
+            include_path.display(),
+            SHA2_COMPRESS.map(|(file, _)| file)
+        );
+        std::process::exit(1);
+    }
+    mode.link();
+    selection.prehash
 }
 
-fn generate_bindings() {
-    let include_path = std::path::PathBuf::from(SLHDSA_C_PATH);
+/// The SHA-2 compression functions of `slhdsa-c`, replaced by the Rust ones
+/// with the `rust-sha2` feature, and the files defining them
+#[cfg(not(feature = "system"))]
+const SHA2_COMPRESS: [(&str, &str); 2] = [
+    ("sha2_256.c", "sha2_256_compress"),
+    ("sha2_512.c", "sha2_512_compress"),
+];
 
-    let mut builder = bindgen::Builder::default();
-    builder = builder.clang_arg(format!("-I{}", include_path.display()));
+/// Name of the C implementation of `function` when it is replaced by the
+/// Rust one
+#[cfg(not(feature = "system"))]
+fn c_name(function: &str) -> String {
+    format!("slhdsa_c_{function}")
+}
 
-    //let pattern = include_path.clone().join("*.h");
-    let pattern = include_path.clone().join("slh_dsa.h");
-    let pattern = pattern.to_str().expect("Path not valid UTF-8");
+/// Name of the Rust implementation of `function`, called by the C code in
+/// its place
+#[cfg(not(feature = "system"))]
+fn rust_name(function: &str) -> String {
+    format!("slhdsa_rs_{function}")
+}
 
-    for entry in glob(pattern).expect("Failed to read glob pattern") {
-        match entry {
-            Ok(path) => {
-                println!("cargo:rerun-if-changed={}", path.display());
-                let path = path.to_str().expect("Path not valid UTF-8");
-                builder = builder.header(path);
+/// Copy the C file at `path` to `OUT_DIR`, with the definition of
+/// `function()` renamed to `renamed()`, and return the path of the copy.
+///
+/// Unlike `keccak_f1600()`, the SHA-2 compression functions are called by
+/// the incremental hashing functions of the file defining them: renaming
+/// the definition through the preprocessor would rename these calls as
+/// well, which would then bypass the Rust implementation. The calls are
+/// still renamed to the Rust implementation by the preprocessor.
+#[cfg(not(feature = "system"))]
+fn rename_definition(path: &Path, function: &str, renamed: &str) -> PathBuf {
+    let source = std::fs::read_to_string(path).unwrap_or_else(|e| {
+        println!("cargo::error=Cannot read {}: {e}", path.display());
+        std::process::exit(1);
+    });
+    let call = format!("{function}(");
+    let is_definition = |line: &str| {
+        let line = line.trim_end();
+        line.starts_with("void") && line.contains(&call) && !line.ends_with(';')
+    };
+    if source.lines().filter(|line| is_definition(line)).count() != 1 {
+        println!(
+            "cargo::error=`rust-sha2`: cannot find the definition of `{function}()` in {}",
+            path.display()
+        );
+        std::process::exit(1);
+    }
+
+    let mut renamed_source = String::with_capacity(source.len());
+    for line in source.lines() {
+        if is_definition(line) {
+            let line = line.replace(&call, &format!("{renamed}("));
+            // Keep `-Wmissing-prototypes` happy
+            renamed_source.push_str(&format!("{};\n", line.trim_end()));
+            renamed_source.push_str(&line);
+        } else {
+            renamed_source.push_str(line);
+        }
+        renamed_source.push('\n');
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("rust-sha2");
+    std::fs::create_dir_all(&out_dir).expect("Cannot create the output directory");
+    let out_path = out_dir.join(path.file_name().unwrap());
+    std::fs::write(&out_path, renamed_source).expect("Cannot write the renamed source");
+    out_path
+}
+
+/// The file of `slhdsa-c` holding the FIPS 205 algorithms, whose stack
+/// buffers are sized by `SLH_MAX_HP` and `SLH_MAX_A`
+#[cfg(not(feature = "system"))]
+const SLH_DSA_C: &str = "slh_dsa.c";
+
+/// Write a file to `OUT_DIR` compiling the C file at `path` with the bounds
+/// of `SLH_EXPERIMENTAL` (`h / d <= 20` and `a <= 24` instead of 9 and 14),
+/// as needed by the custom parameter sets (the `custom` feature, see
+/// `src/wrapper/custom.rs`), and return its path. This enlarges the stack
+/// buffers of signing and verification, so other builds use the file as is.
+///
+/// `SLH_EXPERIMENTAL` is not defined for the whole library, as it also makes
+/// the hash functions count their calls in global variables, which would be
+/// data races between threads. The other bounds, and thus the layout of
+/// `slh_var_t`, are the same in both configurations.
+#[cfg(not(feature = "system"))]
+fn with_experimental_bounds(path: &Path) -> PathBuf {
+    let path = path.canonicalize().unwrap_or_else(|e| {
+        println!("cargo::error=Cannot read {}: {e}", path.display());
+        std::process::exit(1);
+    });
+    let source = format!(
+        "#define SLH_EXPERIMENTAL\n#include \"{}\"\n",
+        path.display().to_string().replace('\\', "/")
+    );
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("experimental");
+    std::fs::create_dir_all(&out_dir).expect("Cannot create the output directory");
+    let out_path = out_dir.join(path.file_name().unwrap());
+    std::fs::write(&out_path, source).expect("Cannot write the wrapped source");
+    out_path
+}
+
+/// The Keccak permutation of `slhdsa-c`, replaced by the Rust one
+/// with the `rust-keccak` feature
+#[cfg(not(feature = "system"))]
+const KECCAK_F1600: &str = "keccak_f1600";
+
+/// Name of the C permutation with the `rust-keccak` feature
+#[cfg(not(feature = "system"))]
+const KECCAK_F1600_C: &str = "slhdsa_c_keccak_f1600";
+
+/// Name of the Rust permutation, called by the C code with the
+/// `rust-keccak` feature
+#[cfg(not(feature = "system"))]
+const KECCAK_F1600_RUST: &str = "slhdsa_rs_keccak_f1600";
+
+/// Whether the C file at `path` holds the definition of `keccak_f1600()`.
+///
+/// Renaming the definition also renames the calls in the same translation
+/// unit, which would then bypass the Rust permutation: the file must not
+/// call it otherwise.
+#[cfg(not(feature = "system"))]
+fn defines_keccak_f1600(path: &Path) -> bool {
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let call = format!("{KECCAK_F1600}(");
+    let defined = source.lines().any(|line| {
+        let line = line.trim_end();
+        line.starts_with("void") && line.contains(&call) && !line.ends_with(';')
+    });
+    if !defined {
+        return false;
+    }
+    if source.matches(&call).count() > 1 {
+        println!(
+            "cargo::error=`rust-keccak`: {} calls `{KECCAK_F1600}()` besides defining it",
+            path.display()
+        );
+        std::process::exit(1);
+    }
+    true
+}
+
+/// Find `slh_dsa.h` in `include_dirs`
+fn find_header(include_dirs: &[PathBuf]) -> PathBuf {
+    let Some(header) = include_dirs
+        .iter()
+        .map(|dir| dir.join("slh_dsa.h"))
+        .find(|path| path.is_file())
+    else {
+        println!("cargo::error=Cannot find slh_dsa.h in {include_dirs:?}");
+        std::process::exit(1);
+    };
+    println!("cargo:rerun-if-changed={}", header.display());
+    header
+}
+
+/// Probe assigning each function of the API to a pointer of the expected
+/// type: any prototype mismatch is a compile error, and any missing symbol
+/// a link error.
+const API_PROBE: &str = r#"
+#include "slh_dsa.h"
+#ifdef SLHDSA_RS_PREHASH
+#include "slh_prehash.h"
+#endif
+
+typedef int (*slhdsa_rs_rbg_t)(uint8_t *x, size_t xlen);
+
+const char *(*const slhdsa_rs_alg_id)(const slh_param_t *) = slh_alg_id;
+size_t (*const slhdsa_rs_pk_sz)(const slh_param_t *) = slh_pk_sz;
+size_t (*const slhdsa_rs_sk_sz)(const slh_param_t *) = slh_sk_sz;
+size_t (*const slhdsa_rs_sig_sz)(const slh_param_t *) = slh_sig_sz;
+int (*const slhdsa_rs_keygen_internal)(uint8_t *, uint8_t *, const uint8_t *,
+    const uint8_t *, const uint8_t *, const slh_param_t *) = slh_keygen_internal;
+size_t (*const slhdsa_rs_sign_internal)(uint8_t *, const uint8_t *, size_t,
+    const uint8_t *, const uint8_t *, const slh_param_t *) = slh_sign_internal;
+int (*const slhdsa_rs_verify_internal)(const uint8_t *, size_t, const uint8_t *,
+    size_t, const uint8_t *, const slh_param_t *) = slh_verify_internal;
+int (*const slhdsa_rs_keygen)(uint8_t *, uint8_t *, slhdsa_rs_rbg_t,
+    const slh_param_t *) = slh_keygen;
+size_t (*const slhdsa_rs_sign)(uint8_t *, const uint8_t *, size_t, const uint8_t *,
+    size_t, const uint8_t *, const uint8_t *, const slh_param_t *) = slh_sign;
+int (*const slhdsa_rs_verify)(const uint8_t *, size_t, const uint8_t *, size_t,
+    const uint8_t *, size_t, const uint8_t *, const slh_param_t *) = slh_verify;
+#ifdef SLHDSA_RS_PREHASH
+size_t (*const slhdsa_rs_hash_sign)(uint8_t *, const uint8_t *, size_t,
+    const uint8_t *, size_t, const char *, const uint8_t *, const uint8_t *,
+    const slh_param_t *) = hash_slh_sign;
+int (*const slhdsa_rs_hash_verify)(const uint8_t *, size_t, const uint8_t *,
+    size_t, const uint8_t *, size_t, const char *, const uint8_t *,
+    const slh_param_t *) = hash_slh_verify;
+#endif
+
+#ifdef SLHDSA_RS_LINK_PROBE
+int main(void)
+{
+  return 0;
+}
+#endif
+"#;
+
+/// Write [`API_PROBE`] to `OUT_DIR`
+fn write_api_probe() -> PathBuf {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let probe = out_dir.join("slhdsa_api_check.c");
+    std::fs::write(&probe, API_PROBE).expect("Failed to write the API check");
+    probe
+}
+
+/// Check that the `slhdsa-c` header declares the API the Rust bindings were
+/// generated from, when building against sources or a library other than
+/// the pinned submodule. The HashSLH-DSA functions of `slh_prehash.h` are
+/// only checked if `prehash` is set.
+///
+/// This compiles [`API_PROBE`], without linking.
+fn check_header(include_dirs: &[PathBuf], prehash: bool) {
+    let header = find_header(include_dirs);
+    let probe = write_api_probe();
+
+    let mut build = cc::Build::new();
+    build
+        .includes(include_dirs)
+        .file(&probe)
+        .flag_if_supported("-Werror=incompatible-pointer-types")
+        .cargo_metadata(false);
+    if prehash {
+        build.define("SLHDSA_RS_PREHASH", None);
+    }
+    if let Err(e) = build.try_compile_intermediates() {
+        println!(
+            "cargo::error={} is not compatible with the API of the pinned slhdsa-c revision: {e}",
+            header.display()
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Parameters of an SLH-DSA parameter set, as in FIPS 205 (Table 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Params {
+    n: usize,
+    h: usize,
+    d: usize,
+    a: usize,
+    k: usize,
+    lg_w: usize,
+}
+
+impl Params {
+    fn pk_len(&self) -> usize {
+        2 * self.n
+    }
+
+    fn sk_len(&self) -> usize {
+        4 * self.n
+    }
+
+    /// Number of WOTS+ chains (FIPS 205, Section 5)
+    fn len(&self) -> usize {
+        let w = 1 << self.lg_w;
+        let len1 = (8 * self.n).div_ceil(self.lg_w);
+        let log2 = (len1 * (w - 1)).ilog2() as usize;
+        len1 + log2 / self.lg_w + 1
+    }
+
+    /// Randomizer, FORS signature and hypertree signature (FIPS 205, Section 9)
+    fn sig_len(&self) -> usize {
+        self.n * (1 + self.k * (1 + self.a) + self.h + self.d * self.len())
+    }
+}
+
+/// The FIPS 205 parameter sets, used when the C parameter tables are not
+/// available (i.e., when linking a system library), and their OIDs
+/// (from <https://www.ietf.org/archive/id/draft-ietf-lamps-x509-slhdsa-09.html#section-3-7>).
+const KNOWN_PARAMETER_SETS: [(&str, Params, &str); 12] = {
+    const fn p(n: usize, h: usize, d: usize, a: usize, k: usize) -> Params {
+        Params {
+            n,
+            h,
+            d,
+            a,
+            k,
+            lg_w: 4,
+        }
+    }
+    [
+        (
+            "slh_dsa_sha2_128s",
+            p(16, 63, 7, 12, 14),
+            "2.16.840.1.101.3.4.3.20",
+        ),
+        (
+            "slh_dsa_sha2_128f",
+            p(16, 66, 22, 6, 33),
+            "2.16.840.1.101.3.4.3.21",
+        ),
+        (
+            "slh_dsa_sha2_192s",
+            p(24, 63, 7, 14, 17),
+            "2.16.840.1.101.3.4.3.22",
+        ),
+        (
+            "slh_dsa_sha2_192f",
+            p(24, 66, 22, 8, 33),
+            "2.16.840.1.101.3.4.3.23",
+        ),
+        (
+            "slh_dsa_sha2_256s",
+            p(32, 64, 8, 14, 22),
+            "2.16.840.1.101.3.4.3.24",
+        ),
+        (
+            "slh_dsa_sha2_256f",
+            p(32, 68, 17, 9, 35),
+            "2.16.840.1.101.3.4.3.25",
+        ),
+        (
+            "slh_dsa_shake_128s",
+            p(16, 63, 7, 12, 14),
+            "2.16.840.1.101.3.4.3.26",
+        ),
+        (
+            "slh_dsa_shake_128f",
+            p(16, 66, 22, 6, 33),
+            "2.16.840.1.101.3.4.3.27",
+        ),
+        (
+            "slh_dsa_shake_192s",
+            p(24, 63, 7, 14, 17),
+            "2.16.840.1.101.3.4.3.28",
+        ),
+        (
+            "slh_dsa_shake_192f",
+            p(24, 66, 22, 8, 33),
+            "2.16.840.1.101.3.4.3.29",
+        ),
+        (
+            "slh_dsa_shake_256s",
+            p(32, 64, 8, 14, 22),
+            "2.16.840.1.101.3.4.3.30",
+        ),
+        (
+            "slh_dsa_shake_256f",
+            p(32, 68, 17, 9, 35),
+            "2.16.840.1.101.3.4.3.31",
+        ),
+    ]
+};
+
+/// A parameter set exported by `slh_dsa.h`
+struct ParamSetDef {
+    /// Name of the C static, e.g. `slh_dsa_sha2_128s`
+    symbol: String,
+    /// Hash family, `sha2` or `shake`
+    family: &'static str,
+    /// The rest of the name, e.g. `128s`
+    suffix: String,
+    params: Params,
+    oid: &'static str,
+}
+
+impl ParamSetDef {
+    /// The FIPS 205 name, e.g. `SLH-DSA-SHA2-128s`
+    fn name(&self) -> String {
+        format!(
+            "SLH-DSA-{}-{}",
+            self.family.to_uppercase(),
+            self.suffix.replace('_', "-")
+        )
+    }
+
+    /// The Rust type, e.g. `SLH_DSA_SHA2_128s`
+    fn ident(&self) -> String {
+        format!("SLH_DSA_{}_{}", self.family.to_uppercase(), self.suffix)
+    }
+
+    /// The cargo feature gating the set, if any, e.g. `sha2-128s`
+    fn feature(&self) -> Option<String> {
+        let feature = format!("{}-{}", self.family, self.suffix.replace('_', "-"));
+        SHA2_PARAMETER_SETS
+            .iter()
+            .chain(SHAKE_PARAMETER_SETS.iter())
+            .any(|f| *f == feature)
+            .then_some(feature)
+    }
+
+    /// The `cfg` predicate gating the set. Sets outside of FIPS 205 have no
+    /// feature of their own: they are enabled with the C parameter tables of
+    /// their hash family, i.e. with any set of that family.
+    fn cfg(&self) -> String {
+        if let Some(feature) = self.feature() {
+            return format!("feature = \"{feature}\"");
+        }
+        let family = if self.family == "sha2" {
+            SHA2_PARAMETER_SETS
+        } else {
+            SHAKE_PARAMETER_SETS
+        };
+        let features: Vec<String> = family
+            .iter()
+            .map(|f| format!("feature = \"{f}\""))
+            .collect();
+        format!("any({})", features.join(", "))
+    }
+}
+
+/// Names of the parameter set statics declared in `header`, e.g.
+/// `extern const slh_param_t slh_dsa_sha2_128s;`
+fn declared_parameter_sets(header: &str) -> Vec<String> {
+    const DECL: &str = "extern const slh_param_t";
+    header
+        .match_indices(DECL)
+        .filter_map(|(i, _)| {
+            let symbol: String = header[i + DECL.len()..]
+                .trim_start()
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect();
+            symbol.starts_with("slh_dsa_").then_some(symbol)
+        })
+        .collect()
+}
+
+/// Parse the parameters of `symbol` from its definition in `sources`, e.g.
+/// `const slh_param_t slh_dsa_sha2_128s = { .n = 16, ... };`, with either
+/// designated initializers or C90-style `/* .n = */ 16` comments.
+fn parse_parameter_table(sources: &[String], symbol: &str) -> Option<Params> {
+    let definition = format!("slh_param_t {symbol}");
+    // Skip the declarations, to find the definition
+    let body = sources.iter().find_map(|src| {
+        src.match_indices(&definition).find_map(|(i, _)| {
+            let rest = src[i + definition.len()..].trim_start().strip_prefix('=')?;
+            let open = rest.find('{')?;
+            let close = rest.find("};")?;
+            Some(&rest[open + 1..close])
+        })
+    })?;
+
+    let field = |name: &str| -> Option<usize> {
+        let pattern = format!(".{name}");
+        let mut rest = body;
+        while let Some(i) = rest.find(&pattern) {
+            rest = &rest[i + pattern.len()..];
+            // Skip longer field names sharing the prefix (`.h` and `.hp`)
+            let Some(value) = rest.trim_start().strip_prefix('=') else {
+                continue;
+            };
+            let value = value.trim_start();
+            let value = value.strip_prefix("*/").unwrap_or(value).trim_start();
+            let digits: String = value.chars().take_while(char::is_ascii_digit).collect();
+            return digits.parse().ok();
+        }
+        None
+    };
+
+    Some(Params {
+        n: field("n")?,
+        h: field("h")?,
+        d: field("d")?,
+        a: field("a")?,
+        k: field("k")?,
+        lg_w: field("lg_w")?,
+    })
+}
+
+/// Read the parameter sets declared by `slh_dsa.h`, with their parameters
+/// from the C parameter tables in `source_dirs`.
+///
+/// Without sources (i.e., with a system library), the parameters of the
+/// FIPS 205 sets are taken from [`KNOWN_PARAMETER_SETS`] instead: the sizes
+/// are then checked against the library by the wrapper (see
+/// `check_system_sizes()` in `src/wrapper.rs`).
+fn parameter_sets(include_dirs: &[PathBuf], source_dirs: &[PathBuf]) -> Vec<ParamSetDef> {
+    let header = find_header(include_dirs);
+    let header = std::fs::read_to_string(&header).expect("Failed to read slh_dsa.h");
+
+    let sources: Vec<String> = source_dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let is_c = path.extension().is_some_and(|ext| ext == "c");
+            is_c.then(|| std::fs::read_to_string(path).ok()).flatten()
+        })
+        .collect();
+
+    let mut sets = Vec::new();
+    for symbol in declared_parameter_sets(&header) {
+        let rest = &symbol["slh_dsa_".len()..];
+        let (family, suffix) = match rest.split_once('_') {
+            Some(("sha2", suffix)) => ("sha2", suffix),
+            Some(("shake", suffix)) => ("shake", suffix),
+            _ => {
+                println!("cargo::warning=Skipping {symbol}: unknown hash family");
+                continue;
+            }
+        };
+        let known = KNOWN_PARAMETER_SETS.iter().find(|(s, _, _)| *s == symbol);
+
+        let params = match (parse_parameter_table(&sources, &symbol), known) {
+            (Some(params), _) => params,
+            (None, Some((_, params, _))) if source_dirs.is_empty() => *params,
+            (None, _) if !source_dirs.is_empty() => {
+                println!(
+                    "cargo::error=Cannot parse the parameter table of {symbol} in {}",
+                    source_dirs
+                        .iter()
+                        .map(|dir| dir.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                std::process::exit(1);
+            }
+            (None, _) => {
+                println!("cargo::error=Cannot find the parameters of {symbol}");
+                std::process::exit(1);
+            }
+        };
+
+        sets.push(ParamSetDef {
+            family,
+            suffix: suffix.to_owned(),
+            params,
+            // Parameter sets outside of FIPS 205 have no assigned OID
+            oid: known.map_or("", |(_, _, oid)| *oid),
+            symbol,
+        });
+    }
+    sets
+}
+
+/// Generate `$OUT_DIR/typenum_sizes.rs`, defining the typenum aliases of
+/// the sizes beyond the predefined `U0`..`U1024` constants
+fn generate_typenum_sizes(sets: &[ParamSetDef]) {
+    let mut sizes: Vec<usize> = sets
+        .iter()
+        .flat_map(|set| {
+            let params = set.params;
+            [params.pk_len(), params.sk_len(), params.sig_len()]
+        })
+        .filter(|size| *size > 1024)
+        .collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    let mut out = String::from("// Generated by build.rs from the slhdsa-c parameter tables\n");
+    for size in sizes {
+        let bits: Vec<&str> = (0..usize::BITS - size.leading_zeros())
+            .map(|i| if (size >> i) & 1 == 1 { "1" } else { "0" })
+            .collect();
+        out += &format!(
+            "\n// {size} ({size:#b})\n#[allow(dead_code)]\npub type U{size} = u_from_bits!({});\n",
+            bits.join(" ")
+        );
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("typenum_sizes.rs"), out).expect("Failed to write the sizes");
+}
+
+/// Generate the `define_param_set!` invocations of each hash family in
+/// `$OUT_DIR/parameter_sets_{sha2,shake}.rs`
+fn generate_parameter_sets(sets: &[ParamSetDef]) {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+
+    for family in ["sha2", "shake"] {
+        let mut out = String::from("// Generated by build.rs from the slhdsa-c parameter tables\n");
+        for set in sets.iter().filter(|set| set.family == family) {
+            let Params {
+                n,
+                h,
+                d,
+                a,
+                k,
+                lg_w,
+            } = set.params;
+            out += &format!("\n// n = {n}, h = {h}, d = {d}, a = {a}, k = {k}, lg_w = {lg_w}\n");
+            let cfg = set.cfg();
+            // The committed bindings only declare the FIPS 205 sets
+            let ffi_path = if set.feature().is_some() {
+                format!("crate::ffi::{}", set.symbol)
+            } else {
+                out += &format!(
+                    "#[cfg({cfg})]\nunsafe extern \"C\" {{\n    \
+                     static {symbol}: crate::ffi::slh_param_t;\n}}\n",
+                    symbol = set.symbol,
+                );
+                set.symbol.clone()
+            };
+            out += &format!("#[cfg({cfg})]\n");
+            let (vk, sk, sig) = (
+                set.params.pk_len(),
+                set.params.sk_len(),
+                set.params.sig_len(),
+            );
+            out += &format!(
+                "define_param_set!(\n    \"{name}\",\n    \"{oid}\",\n    \
+                 {vk},\n    typenum::U{vk},\n    {sk},\n    typenum::U{sk},\n    \
+                 {sig},\n    typenum::U{sig},\n    {ident},\n    {ffi_path},\n    {family_ident}\n);\n",
+                name = set.name(),
+                oid = set.oid,
+                ident = set.ident(),
+                family_ident = if family == "sha2" { "Sha2" } else { "Shake" },
+            );
+        }
+        std::fs::write(out_dir.join(format!("parameter_sets_{family}.rs")), out)
+            .expect("Failed to write the parameter sets");
+    }
+}
+
+/// A prebuilt `libslhdsa`
+#[cfg(feature = "system")]
+struct SystemLibrary {
+    include_dirs: Vec<PathBuf>,
+    link_dirs: Vec<PathBuf>,
+    libs: Vec<String>,
+}
+
+/// Link against a prebuilt `libslhdsa`, either from `SLHDSA_C_LIB_DIR` or
+/// found with `pkg-config`.
+///
+/// `slhdsa-c` has no version number, neither in its headers nor as a
+/// `pkg-config` file: the library is checked by [`check_library()`] and
+/// [`check_parameter_sets()`] instead.
+#[cfg(feature = "system")]
+fn link_system_library() -> SystemLibrary {
+    const LIB_DIR_ENV: &str = "SLHDSA_C_LIB_DIR";
+    const INCLUDE_DIR_ENV: &str = "SLHDSA_C_INCLUDE_DIR";
+    const STATIC_ENV: &str = "SLHDSA_C_STATIC";
+    const LIB_NAME: &str = "slhdsa";
+
+    for var in [LIB_DIR_ENV, INCLUDE_DIR_ENV, STATIC_ENV] {
+        println!("cargo:rerun-if-env-changed={var}");
+    }
+    let statik = std::env::var_os(STATIC_ENV).is_some_and(|v| v != "0");
+
+    if let Some(lib_dir) = std::env::var_os(LIB_DIR_ENV) {
+        let lib_dir = PathBuf::from(lib_dir);
+        println!("cargo:rustc-link-search=native={}", lib_dir.display());
+        let kind = if statik { "static=" } else { "" };
+        println!("cargo:rustc-link-lib={kind}{LIB_NAME}");
+
+        // Default to the conventional `<prefix>/include` next to `<prefix>/lib`
+        let include_dir = std::env::var_os(INCLUDE_DIR_ENV)
+            .map_or_else(|| lib_dir.join("..").join("include"), PathBuf::from);
+        return SystemLibrary {
+            include_dirs: vec![include_dir],
+            link_dirs: vec![lib_dir],
+            libs: vec![LIB_NAME.to_owned()],
+        };
+    }
+
+    match pkg_config::Config::new().statik(statik).probe(LIB_NAME) {
+        Ok(library) => {
+            println!(
+                "cargo:warning=Using system {LIB_NAME} {} (pkg-config)",
+                library.version
+            );
+            SystemLibrary {
+                include_dirs: library.include_paths,
+                link_dirs: library.link_paths,
+                libs: library.libs,
             }
-            Err(e) => eprintln!("Glob error: {:?}", e),
         }
+        Err(e) => {
+            println!(
+                "cargo::error=Cannot find {LIB_NAME} with pkg-config, and {LIB_DIR_ENV} is not set: {e}"
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Check that `library` exports the API declared by its header, by linking
+/// [`API_PROBE`] against it. Headers and libraries of different versions
+/// would otherwise only fail when linking the final binary, or not at all.
+///
+/// Skipped on bare-metal targets, where the probe cannot be linked into a
+/// program.
+#[cfg(feature = "system")]
+fn check_library(library: &SystemLibrary, prehash: bool) {
+    if bare_metal() {
+        return;
+    }
+    let probe = write_api_probe();
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let exe = out_dir.join("slhdsa_api_check");
+
+    let mut defines = vec!["SLHDSA_RS_LINK_PROBE"];
+    if prehash {
+        defines.push("SLHDSA_RS_PREHASH");
+    }
+    if let Err(e) = link_program(library, &probe, &exe, &defines) {
+        println!(
+            "cargo::error=The system slhdsa library does not export the API of its header: {e}"
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Compile the C program `source` with `defines`, and link it against
+/// `library` into `exe`
+#[cfg(feature = "system")]
+fn link_program(
+    library: &SystemLibrary,
+    source: &Path,
+    exe: &Path,
+    defines: &[&str],
+) -> Result<(), String> {
+    let compiler = cc::Build::new()
+        .includes(&library.include_dirs)
+        .cargo_metadata(false)
+        .get_compiler();
+    let mut cmd = compiler.to_command();
+    for define in defines {
+        cmd.arg(format!("-D{define}"));
+    }
+    cmd.arg(source).arg("-o").arg(exe);
+    for dir in &library.link_dirs {
+        cmd.arg(format!("-L{}", dir.display()));
+    }
+    for lib in &library.libs {
+        cmd.arg(format!("-l{lib}"));
+    }
+    match cmd.output() {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).replace('\n', " ")),
+        Err(e) => Err(format!("cannot run the C compiler: {e}")),
+    }
+}
+
+/// Check that the parameter sets of `library` have the names and sizes the
+/// Rust types are generated with, by running a program printing them.
+///
+/// The parameters of a prebuilt library are not read from its sources, but
+/// taken from FIPS 205: a library with other parameter tables (e.g., patched)
+/// would write keys and signatures of another size than the Rust buffers.
+///
+/// This needs to run the program, so it is skipped when cross-compiling:
+/// the sizes are then only checked at runtime (see `check_system_sizes()`
+/// in `src/wrapper.rs`).
+#[cfg(feature = "system")]
+fn check_parameter_sets(library: &SystemLibrary, sets: &[ParamSetDef]) {
+    if bare_metal() || std::env::var("TARGET").ok() != std::env::var("HOST").ok() {
+        println!(
+            "cargo::warning=Cross-compiling: the parameter sets of the system slhdsa library \
+             are only checked at runtime"
+        );
+        return;
+    }
+
+    let mut source =
+        String::from("#include <stdio.h>\n#include \"slh_dsa.h\"\n\nint main(void)\n{\n");
+    for set in sets {
+        source += &format!(
+            "  printf(\"%s %lu %lu %lu\\n\", slh_alg_id(&{s}), (unsigned long) slh_pk_sz(&{s}),\n         \
+             (unsigned long) slh_sk_sz(&{s}), (unsigned long) slh_sig_sz(&{s}));\n",
+            s = set.symbol
+        );
+    }
+    source += "  return 0;\n}\n";
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let probe = out_dir.join("slhdsa_params_check.c");
+    std::fs::write(&probe, source).expect("Failed to write the parameter set check");
+    let exe = out_dir.join("slhdsa_params_check");
+    if let Err(e) = link_program(library, &probe, &exe, &[]) {
+        println!("cargo::error=Cannot build the parameter set check: {e}");
+        std::process::exit(1);
+    }
+    let output = match std::process::Command::new(&exe).output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            println!(
+                "cargo::error=The parameter set check failed: {}",
+                output.status
+            );
+            std::process::exit(1);
+        }
+        Err(e) => {
+            println!("cargo::error=Cannot run the parameter set check: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    let mut lines = output.lines();
+    for set in sets {
+        let line = lines.next().unwrap_or_default();
+        let expected = format!(
+            "{} {} {} {}",
+            set.name(),
+            set.params.pk_len(),
+            set.params.sk_len(),
+            set.params.sig_len()
+        );
+        if line != expected {
+            println!(
+                "cargo::error=The system slhdsa library does not implement {} of FIPS 205: \
+                 got `{line}` (name, key and signature sizes), expected `{expected}`",
+                set.symbol
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Clang arguments to parse the headers for the target rather than the host
+///
+/// The sysroot is taken from `BINDGEN_SYSROOT`, or else asked to the target C
+/// compiler found by `cc` (e.g., `arm-none-eabi-gcc -print-sysroot`).
+#[cfg(feature = "bindgen")]
+fn bindgen_target_args() -> Vec<String> {
+    let target = std::env::var("TARGET").unwrap();
+    let host = std::env::var("HOST").unwrap();
+    println!("cargo:rerun-if-env-changed=BINDGEN_SYSROOT");
+
+    if target == host {
+        return Vec::new();
+    }
+
+    // Clang does not know the Rust-specific names of some targets
+    let clang_target = match target.split_once('-') {
+        Some((arch, rest)) if arch.starts_with("riscv32") => format!("riscv32-{rest}"),
+        Some((arch, rest)) if arch.starts_with("riscv64") => format!("riscv64-{rest}"),
+        _ => target.clone(),
+    };
+    let mut args = vec![format!("--target={clang_target}")];
+
+    let sysroot = std::env::var("BINDGEN_SYSROOT").ok().or_else(|| {
+        let compiler = cc::Build::new().get_compiler();
+        let output = compiler.to_command().arg("-print-sysroot").output().ok()?;
+        let sysroot = String::from_utf8(output.stdout).ok()?;
+        let sysroot = sysroot.trim();
+        (output.status.success() && !sysroot.is_empty()).then(|| sysroot.to_owned())
+    });
+    if let Some(sysroot) = sysroot {
+        args.push(format!("--sysroot={sysroot}"));
+    }
+    args
+}
+
+#[cfg(feature = "bindgen")]
+fn generate_bindings(include_dirs: &[PathBuf]) {
+    let header = find_header(include_dirs);
+
+    let mut builder = bindgen::Builder::default().clang_args(bindgen_target_args());
+    for dir in include_dirs {
+        builder = builder.clang_arg(format!("-I{}", dir.display()));
+    }
+    builder = builder.header(header.to_str().expect("Path not valid UTF-8"));
+
+    // The HashSLH-DSA functions are declared in a separate header, which
+    // prebuilt libraries may not ship
+    let prehash_header = header.with_file_name("slh_prehash.h");
+    if prehash_header.is_file() {
+        println!("cargo:rerun-if-changed={}", prehash_header.display());
+        builder = builder.header(prehash_header.to_str().expect("Path not valid UTF-8"));
     }
 
     // Filter relevant interfaces
-    let builder = builder.allowlist_function("slh_.*").allowlist_var("slh_.*");
+    let builder = builder
+        .allowlist_function("(hash_)?slh_.*")
+        .allowlist_var("slh_.*");
+
+    // The parameter set struct is opaque on the Rust side (see `src/ffi.rs`)
+    let builder = builder
+        .blocklist_type("slh_param_s")
+        .blocklist_type("slh_param_t");
 
     // Generate Rust bindings from the header
     let bindings = builder
@@ -95,9 +1333,77 @@ fn generate_bindings() {
 }
 
 fn main() {
-    // Compile C sources
-    compile_c_sources();
+    println!(
+        "cargo::rustc-check-cfg=cfg(slhdsa_c_sha2, slhdsa_c_shake, slhdsa_c_keccak, slhdsa_c_sha2_compress, slhdsa_fast_sets)"
+    );
+    // The tests signing many messages only use the "f" parameter sets, as
+    // signing with the "s" ones is too slow
+    if SHA2_PARAMETER_SETS
+        .iter()
+        .chain(SHAKE_PARAMETER_SETS.iter())
+        .any(|f| f.ends_with('f') && feature_enabled(f))
+    {
+        println!("cargo::rustc-cfg=slhdsa_fast_sets");
+    }
+    let mode = BuildMode::from_env();
 
-    // Generate Rust bindings from the header
-    generate_bindings();
+    // Link a prebuilt library, or compile the C sources
+    #[cfg(feature = "system")]
+    let (include_dirs, source_dirs, custom, prehash, library) = {
+        for feature in ["rust-keccak", "rust-sha2", "stream", "prepared", "custom"] {
+            if feature_enabled(feature) {
+                println!(
+                    "cargo::error=The `{feature}` feature needs to compile slhdsa-c: it is not compatible with `system`"
+                );
+                std::process::exit(1);
+            }
+        }
+        if !mode.sanitizers.is_empty() || mode.hardening {
+            println!(
+                "cargo::warning=Sanitizers and hardening flags have no effect on the system slhdsa-c library"
+            );
+        }
+        let mode = BuildMode {
+            sanitizers: Vec::new(),
+            hardening: false,
+            ..mode
+        };
+        mode.report("system");
+        // HashSLH-DSA is only used by the ACVP runner
+        let prehash = feature_enabled("acvp");
+        let library = link_system_library();
+        check_library(&library, prehash);
+        (
+            library.include_dirs.clone(),
+            Vec::new(),
+            true,
+            prehash,
+            library,
+        )
+    };
+    #[cfg(not(feature = "system"))]
+    let (include_dirs, source_dirs, custom, prehash) = {
+        let (src_dir, custom) = source_dir();
+        let prehash = compile_c_sources(&src_dir, &mode);
+        mode.report(if custom { "custom" } else { "bundled" });
+        (vec![src_dir.clone()], vec![src_dir], custom, prehash)
+    };
+
+    // Derive the Rust parameter sets from the ones of the C library
+    let sets = parameter_sets(&include_dirs, &source_dirs);
+    generate_typenum_sizes(&sets);
+    generate_parameter_sets(&sets);
+    #[cfg(feature = "system")]
+    check_parameter_sets(&library, &sets);
+
+    // The pre-generated bindings match the pinned submodule: make sure any
+    // other copy of slhdsa-c exposes the same API
+    if custom {
+        check_header(&include_dirs, prehash);
+    }
+
+    // Generate Rust bindings from the header. Otherwise, the pre-generated
+    // `src/ffi/bindings.rs` is used, and libclang is not needed.
+    #[cfg(feature = "bindgen")]
+    generate_bindings(&include_dirs);
 }
@@ -0,0 +1,61 @@
+/*
+ * Parameter sets defined at runtime by `slhdsa-c-rs` (see
+ * `src/wrapper/custom.rs`).
+ *
+ * The layout of `slh_param_t` is private to `slhdsa-c`, so it is only ever
+ * handled from C: a custom parameter set starts as a copy of a built-in one
+ * with the same hash family and security parameter `n`, which provides the
+ * hash functions, and then gets its own tree parameters and digest length.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+#include <string.h>
+
+#include "slh_param.h"
+
+#ifndef SLHDSA_RS_PARAM_STORAGE_LEN
+#error "SLHDSA_RS_PARAM_STORAGE_LEN is defined by build.rs"
+#endif
+
+/*
+ * The Rust side stores each custom `slh_param_t` in a buffer of
+ * `SLHDSA_RS_PARAM_STORAGE_LEN` bytes. C99 has no `_Static_assert`: the size
+ * of this array is negative, and the build fails, if `slh_param_t` grows
+ * beyond it.
+ */
+typedef char slhdsa_rs_param_fits_storage
+    [(sizeof(slh_param_t) <= SLHDSA_RS_PARAM_STORAGE_LEN) ? 1 : -1];
+
+size_t slhdsa_rs_param_size(void);
+
+size_t slhdsa_rs_param_size(void) { return sizeof(slh_param_t); }
+
+int slhdsa_rs_param_init(void *buf, size_t buf_len, const slh_param_t *base,
+                         const char *alg_id, uint32_t h, uint32_t d,
+                         uint32_t a, uint32_t k, uint32_t lg_w, uint32_t m);
+
+int slhdsa_rs_param_init(void *buf, size_t buf_len, const slh_param_t *base,
+                         const char *alg_id, uint32_t h, uint32_t d,
+                         uint32_t a, uint32_t k, uint32_t lg_w, uint32_t m)
+{
+  slh_param_t prm;
+
+  if (buf_len < sizeof(slh_param_t) || d == 0 || h % d != 0)
+  {
+    return -1;
+  }
+
+  memcpy(&prm, base, sizeof(prm));
+  prm.alg_id = alg_id;
+  prm.h = h;
+  prm.d = d;
+  prm.hp = h / d;
+  prm.a = a;
+  prm.k = k;
+  prm.lg_w = lg_w;
+  prm.m = m;
+  memcpy(buf, &prm, sizeof(prm));
+
+  return 0;
+}
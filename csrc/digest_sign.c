@@ -0,0 +1,116 @@
+/*
+ * Signing and verifying from a message randomizer `R` and digest computed
+ * by `slhdsa-c-rs` (see `src/wrapper/stream.rs`), for messages that are
+ * streamed rather than passed to the C library as a single buffer.
+ *
+ * `slh_sign_internal()` and `slh_verify_internal()` only use the message
+ * through `prm->prf_msg` and `prm->h_msg`. They are called here with a copy
+ * of the parameter set in which both functions return the precomputed
+ * values, passed as the "message" `n || R || digest`, where `n` is a single
+ * byte.
+ *
+ * Like `custom_params.c`, this relies on the private layout of
+ * `slh_param_t`.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+#include <string.h>
+
+#include "slh_dsa.h"
+#include "slh_param.h"
+
+/* Bounds of `n` and of the digest length `m` of all the parameter sets */
+#define SLHDSA_RS_MAX_N 32
+#define SLHDSA_RS_MAX_M 64
+
+uint32_t slhdsa_rs_digest_len(const slh_param_t *prm);
+
+size_t slhdsa_rs_sign_digest(uint8_t *sig, const uint8_t *r,
+                             const uint8_t *digest, const uint8_t *sk,
+                             const slh_param_t *prm);
+
+int slhdsa_rs_verify_digest(const uint8_t *digest, const uint8_t *sig,
+                            size_t sig_sz, const uint8_t *pk,
+                            const slh_param_t *prm);
+
+/* `PRF_msg`: the randomizer `R` */
+static void prf_msg_precomputed(slh_var_t *var, uint8_t *h,
+                                const uint8_t *opt_rand, const uint8_t *m,
+                                size_t m_sz)
+{
+  (void)var;
+  (void)opt_rand;
+  (void)m_sz;
+  memcpy(h, m + 1, m[0]);
+}
+
+/* `H_msg`: the digest, after `R` */
+static void h_msg_precomputed(slh_var_t *var, uint8_t *h, const uint8_t *r,
+                              const uint8_t *m, size_t m_sz)
+{
+  (void)var;
+  (void)r;
+  memcpy(h, m + 1 + m[0], m_sz - 1 - m[0]);
+}
+
+/*
+ * Set up `prm` and `m` from `base`, `R` and the digest, and return the
+ * length of `m`, or 0 if `base` is beyond the bounds above.
+ */
+static size_t precomputed(slh_param_t *prm, uint8_t *m, const uint8_t *r,
+                          const uint8_t *digest, const slh_param_t *base)
+{
+  if (base->n > SLHDSA_RS_MAX_N || base->m > SLHDSA_RS_MAX_M)
+  {
+    return 0;
+  }
+
+  memcpy(prm, base, sizeof(*prm));
+  prm->prf_msg = prf_msg_precomputed;
+  prm->h_msg = h_msg_precomputed;
+
+  m[0] = (uint8_t)base->n;
+  memcpy(m + 1, r, base->n);
+  memcpy(m + 1 + base->n, digest, base->m);
+
+  return 1 + base->n + base->m;
+}
+
+uint32_t slhdsa_rs_digest_len(const slh_param_t *prm) { return prm->m; }
+
+size_t slhdsa_rs_sign_digest(uint8_t *sig, const uint8_t *r,
+                             const uint8_t *digest, const uint8_t *sk,
+                             const slh_param_t *prm)
+{
+  slh_param_t precomputed_prm;
+  uint8_t m[1 + SLHDSA_RS_MAX_N + SLHDSA_RS_MAX_M];
+  size_t m_sz = precomputed(&precomputed_prm, m, r, digest, prm);
+
+  if (m_sz == 0)
+  {
+    return 0;
+  }
+  return slh_sign_internal(sig, m, m_sz, sk, NULL, &precomputed_prm);
+}
+
+int slhdsa_rs_verify_digest(const uint8_t *digest, const uint8_t *sig,
+                            size_t sig_sz, const uint8_t *pk,
+                            const slh_param_t *prm)
+{
+  slh_param_t precomputed_prm;
+  uint8_t m[1 + SLHDSA_RS_MAX_N + SLHDSA_RS_MAX_M];
+  size_t m_sz;
+
+  /* `R` is the start of the signature */
+  if (sig_sz != slh_sig_sz(prm))
+  {
+    return 0;
+  }
+  m_sz = precomputed(&precomputed_prm, m, sig, digest, prm);
+  if (m_sz == 0)
+  {
+    return 0;
+  }
+  return slh_verify_internal(m, m_sz, sig, sig_sz, pk, &precomputed_prm);
+}
@@ -0,0 +1,194 @@
+/*
+ * Keys prepared for repeated operations (see `src/wrapper/prepared.rs`).
+ *
+ * `slh_sign_internal()` and `slh_verify_internal()` start by deriving the
+ * per-key state `slh_var_t` with `prm->mk_var`, which includes the
+ * `PK.seed`-dependent SHA-2 midstates. A prepared key keeps that state, next
+ * to a copy of the parameter set whose `mk_var` restores it instead of
+ * deriving it again.
+ *
+ * The state is captured during one operation run by `slhdsa-c-rs` at
+ * preparation, with the arguments that operation passes to `mk_var`. Later
+ * calls with other arguments (another key, or a sign rather than a verify)
+ * derive the state as usual.
+ *
+ * Like `custom_params.c`, this relies on the private layout of
+ * `slh_param_t`, and also on that of `slh_var_t`: the only pointer of
+ * `slh_var_t` into itself is `adrs`, set by `mk_var` to `&var->t_adrs`, so
+ * that a copy with `adrs` reset is the state derived at the address of the
+ * copy. The types of both fields are checked below; the preparation also
+ * checks that restoring the state gives the same bytes as deriving it, and
+ * leaves the key unprepared otherwise.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+#include <string.h>
+
+#include "slh_dsa.h"
+#include "slh_param.h"
+
+/* Bound of `n` of all the parameter sets */
+#define SLHDSA_RS_MAX_N 32
+
+/* States of `slhdsa_rs_prepared_t` */
+#define SLHDSA_RS_CAPTURING 0
+#define SLHDSA_RS_PREPARED 1
+#define SLHDSA_RS_UNPREPARED 2
+
+typedef void (*slhdsa_rs_mk_var_t)(slh_var_t *var, const uint8_t *pk,
+                                   const uint8_t *sk, const slh_param_t *prm);
+
+typedef struct
+{
+  /* First, so that `mk_var` finds the rest from its `prm` argument */
+  slh_param_t prm;
+  slhdsa_rs_mk_var_t base_mk_var;
+  int state;
+  /* The arguments of the captured `mk_var` call (`NULL` or a copy) */
+  int has_pk, has_sk;
+  uint8_t pk[2 * SLHDSA_RS_MAX_N];
+  uint8_t sk[4 * SLHDSA_RS_MAX_N];
+  slh_var_t var;
+} slhdsa_rs_prepared_t;
+
+/* `adrs` is a pointer to the type of `t_adrs` (see above) */
+typedef char slhdsa_rs_var_adrs_check
+    [sizeof(((slh_var_t *)0)->adrs - &((slh_var_t *)0)->t_adrs)];
+
+/* The Rust storage is a slice of `u128` */
+typedef struct
+{
+  char c;
+  slhdsa_rs_prepared_t prepared;
+} slhdsa_rs_prepared_align_t;
+typedef char slhdsa_rs_prepared_align_check
+    [(offsetof(slhdsa_rs_prepared_align_t, prepared) <= 16) ? 1 : -1];
+
+size_t slhdsa_rs_prepared_size(void);
+
+const slh_param_t *slhdsa_rs_prepare(void *buf, const slh_param_t *base);
+
+int slhdsa_rs_is_prepared(const void *buf);
+
+/* Whether `pk` and `sk` are the arguments of the captured call */
+static int same_key(const slhdsa_rs_prepared_t *prep, const uint8_t *pk,
+                    const uint8_t *sk)
+{
+  uint32_t n = prep->prm.n;
+
+  if ((pk != NULL) != prep->has_pk || (sk != NULL) != prep->has_sk)
+  {
+    return 0;
+  }
+  if (pk != NULL && memcmp(pk, prep->pk, 2 * n) != 0)
+  {
+    return 0;
+  }
+  return sk == NULL || memcmp(sk, prep->sk, 4 * n) == 0;
+}
+
+/* Restore the state of `prep` into `var` */
+static void restore(slh_var_t *var, const slhdsa_rs_prepared_t *prep)
+{
+  memcpy(var, &prep->var, sizeof(*var));
+  var->adrs = &var->t_adrs;
+}
+
+static void mk_var_prepared(slh_var_t *var, const uint8_t *pk,
+                            const uint8_t *sk, const slh_param_t *prm)
+{
+  const slhdsa_rs_prepared_t *prep = (const slhdsa_rs_prepared_t *)prm;
+
+  if (same_key(prep, pk, sk))
+  {
+    restore(var, prep);
+  }
+  else
+  {
+    prep->base_mk_var(var, pk, sk, prm);
+  }
+}
+
+/*
+ * Derive the state of `pk` and `sk` into `prep`, and return whether
+ * restoring it gives the derived state.
+ */
+static int capture(slhdsa_rs_prepared_t *prep, const uint8_t *pk,
+                   const uint8_t *sk)
+{
+  const slh_param_t *prm = &prep->prm;
+  slh_var_t var, expected;
+
+  memset(&prep->var, 0, sizeof(prep->var));
+  memset(&var, 0, sizeof(var));
+  prep->base_mk_var(&prep->var, pk, sk, prm);
+  prep->base_mk_var(&var, pk, sk, prm);
+
+  /* Restore the state over one derived elsewhere */
+  memcpy(&expected, &var, sizeof(expected));
+  restore(&var, prep);
+  if (memcmp(&var, &expected, sizeof(var)) != 0)
+  {
+    return 0;
+  }
+
+  prep->has_pk = pk != NULL;
+  prep->has_sk = sk != NULL;
+  if (pk != NULL)
+  {
+    memcpy(prep->pk, pk, 2 * prm->n);
+  }
+  if (sk != NULL)
+  {
+    memcpy(prep->sk, sk, 4 * prm->n);
+  }
+  return 1;
+}
+
+static void mk_var_capture(slh_var_t *var, const uint8_t *pk,
+                           const uint8_t *sk, const slh_param_t *prm)
+{
+  slhdsa_rs_prepared_t *prep = (slhdsa_rs_prepared_t *)prm;
+
+  prep->base_mk_var(var, pk, sk, prm);
+  if (prep->state != SLHDSA_RS_CAPTURING)
+  {
+    return;
+  }
+  if (capture(prep, pk, sk))
+  {
+    prep->state = SLHDSA_RS_PREPARED;
+    prep->prm.mk_var = mk_var_prepared;
+  }
+  else
+  {
+    prep->state = SLHDSA_RS_UNPREPARED;
+  }
+}
+
+size_t slhdsa_rs_prepared_size(void) { return sizeof(slhdsa_rs_prepared_t); }
+
+const slh_param_t *slhdsa_rs_prepare(void *buf, const slh_param_t *base)
+{
+  slhdsa_rs_prepared_t *prep = buf;
+
+  memset(prep, 0, sizeof(*prep));
+  memcpy(&prep->prm, base, sizeof(prep->prm));
+  prep->base_mk_var = base->mk_var;
+  if (base->n > SLHDSA_RS_MAX_N)
+  {
+    prep->state = SLHDSA_RS_UNPREPARED;
+  }
+  else
+  {
+    prep->state = SLHDSA_RS_CAPTURING;
+    prep->prm.mk_var = mk_var_capture;
+  }
+  return &prep->prm;
+}
+
+int slhdsa_rs_is_prepared(const void *buf)
+{
+  return ((const slhdsa_rs_prepared_t *)buf)->state == SLHDSA_RS_PREPARED;
+}
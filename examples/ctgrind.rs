@@ -0,0 +1,166 @@
+//! Constant-time check of key generation and signing under Valgrind.
+//!
+//! Following ctgrind (Langley, 2010), the secret inputs are left
+//! uninitialized: Memcheck then reports any branch ("Conditional jump or
+//! move depends on uninitialised value(s)") or memory address ("Use of
+//! uninitialised value") derived from them.
+//!
+//! - `keygen`: `slh_keygen_internal()` with an undefined `SK.seed`;
+//! - `sign`: deterministic `slh_sign_internal()` with an undefined `SK.seed`.
+//!
+//! `SK.prf` and `PK.root` are defined when signing: `SK.prf` only feeds
+//! `PRF_msg`, whose output `R` is public (it starts the signature), and the
+//! message digest, which selects the FORS and hypertree leaves, is public
+//! too. Leaving them undefined would report every use of the digest. The
+//! signing key therefore does not match a public key, and the signatures do
+//! not verify: they are never read.
+//!
+//! # Usage
+//!
+//! ```sh
+//! cargo build --release --example ctgrind
+//! valgrind --error-exitcode=1 target/release/examples/ctgrind \
+//!     [--param SLH-DSA-SHAKE-128f]... [--op sign|keygen]...
+//! ```
+//!
+//! Without `--param` or `--op`, every parameter set and operation is
+//! checked.
+
+use slhdsa_c_rs::ffi;
+
+const MSG: &[u8] = b"slhdsa-c-rs constant-time test";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Sign,
+    Keygen,
+}
+
+impl Operation {
+    fn name(self) -> &'static str {
+        match self {
+            Operation::Sign => "sign",
+            Operation::Keygen => "keygen",
+        }
+    }
+}
+
+/// The parameter sets, by name
+fn parameter_sets() -> [(&'static str, &'static ffi::slh_param_t); 12] {
+    // SAFETY: The parameter sets are immutable C statics.
+    unsafe {
+        [
+            ("SLH-DSA-SHA2-128s", &ffi::slh_dsa_sha2_128s),
+            ("SLH-DSA-SHA2-128f", &ffi::slh_dsa_sha2_128f),
+            ("SLH-DSA-SHA2-192s", &ffi::slh_dsa_sha2_192s),
+            ("SLH-DSA-SHA2-192f", &ffi::slh_dsa_sha2_192f),
+            ("SLH-DSA-SHA2-256s", &ffi::slh_dsa_sha2_256s),
+            ("SLH-DSA-SHA2-256f", &ffi::slh_dsa_sha2_256f),
+            ("SLH-DSA-SHAKE-128s", &ffi::slh_dsa_shake_128s),
+            ("SLH-DSA-SHAKE-128f", &ffi::slh_dsa_shake_128f),
+            ("SLH-DSA-SHAKE-192s", &ffi::slh_dsa_shake_192s),
+            ("SLH-DSA-SHAKE-192f", &ffi::slh_dsa_shake_192f),
+            ("SLH-DSA-SHAKE-256s", &ffi::slh_dsa_shake_256s),
+            ("SLH-DSA-SHAKE-256f", &ffi::slh_dsa_shake_256f),
+        ]
+    }
+}
+
+/// A heap buffer of `len` bytes whose first `undefined` bytes are left
+/// uninitialized, and the others set to `fill`. Fresh heap memory is
+/// undefined for Memcheck, unlike stack slots which may be reused.
+fn secret_buffer(len: usize, undefined: usize, fill: u8) -> Box<[std::mem::MaybeUninit<u8>]> {
+    let mut buf = Box::new_uninit_slice(len);
+    for byte in &mut buf[undefined..] {
+        byte.write(fill);
+    }
+    buf
+}
+
+fn check(prm: &ffi::slh_param_t, op: Operation) -> Result<(), String> {
+    let prm = core::ptr::from_ref(prm);
+    // SAFETY: `prm` is a valid parameter set.
+    let (n, sig_sz) = unsafe { (ffi::slh_sk_sz(prm) / 4, ffi::slh_sig_sz(prm)) };
+
+    match op {
+        Operation::Keygen => {
+            let sk_seed = secret_buffer(n, n, 0);
+            let sk_prf = vec![0x01; n];
+            let pk_seed = vec![0x02; n];
+            let mut sk = vec![0; 4 * n];
+            let mut pk = vec![0; 2 * n];
+            // SAFETY: The buffers have the lengths expected for `prm`; the
+            // C code may read uninitialized bytes through a pointer.
+            let ret = unsafe {
+                ffi::slh_keygen_internal(
+                    sk.as_mut_ptr(),
+                    pk.as_mut_ptr(),
+                    sk_seed.as_ptr().cast(),
+                    sk_prf.as_ptr(),
+                    pk_seed.as_ptr(),
+                    prm,
+                )
+            };
+            if ret != 0 {
+                return Err(format!("slh_keygen_internal() failed: {ret}"));
+            }
+        }
+        Operation::Sign => {
+            // SK.seed || SK.prf || PK.seed || PK.root
+            let sk = secret_buffer(4 * n, n, 0x01);
+            let mut sig = vec![0; sig_sz];
+            // SAFETY: As above.
+            let ret = unsafe {
+                ffi::slh_sign_internal(
+                    sig.as_mut_ptr(),
+                    MSG.as_ptr(),
+                    MSG.len(),
+                    sk.as_ptr().cast(),
+                    core::ptr::null(),
+                    prm,
+                )
+            };
+            if ret != sig_sz {
+                return Err(format!("slh_sign_internal() failed: {ret}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), String> {
+    let mut params = Vec::new();
+    let mut operations = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {arg}"));
+        match arg.as_str() {
+            "--param" => params.push(value()?),
+            "--op" => operations.push(match value()?.as_str() {
+                "sign" => Operation::Sign,
+                "keygen" => Operation::Keygen,
+                other => return Err(format!("Unknown operation: {other}")),
+            }),
+            other => return Err(format!("Unknown argument: {other}")),
+        }
+    }
+    if operations.is_empty() {
+        operations = vec![Operation::Sign, Operation::Keygen];
+    }
+
+    let mut checked = 0;
+    for (name, prm) in parameter_sets() {
+        if !params.is_empty() && !params.iter().any(|p| p == name) {
+            continue;
+        }
+        for &operation in &operations {
+            eprintln!("Checking {name} {}...", operation.name());
+            check(prm, operation)?;
+            checked += 1;
+        }
+    }
+    if checked == 0 {
+        return Err("No parameter set selected".into());
+    }
+    Ok(())
+}
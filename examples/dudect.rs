@@ -0,0 +1,381 @@
+//! dudect-style constant-time test for signing and key generation.
+//!
+//! Following "dude, is my code constant time?" (Reparaz, Balasch and
+//! Verbauwhede, 2017), each operation is timed over two classes of inputs:
+//! a fixed secret (class 0) and fresh random secrets (class 1), with classes
+//! interleaved at random. The timing distributions are then compared with
+//! Welch's t-test, both on the raw measurements and after cropping them at
+//! several percentiles. A `|t|` above 4.5 is strong evidence that timing
+//! depends on the secret.
+//!
+//! - `sign`: deterministic signing of a fixed message, with a fixed vs random
+//!   secret key;
+//! - `keygen`: key generation with fixed vs random seeds.
+//!
+//! # Usage
+//!
+//! ```sh
+//! cargo run --release --example dudect -- \
+//!     [--samples N] [--param SLH-DSA-SHAKE-128f]... [--op sign|keygen]... \
+//!     [--output docs/constant-time/report.md]
+//! ```
+//!
+//! Without `--param` or `--op`, every parameter set and operation is tested.
+//! The report is written to `--output` as Markdown (stdout by default).
+
+use std::fmt::Write as _;
+use std::time::Instant;
+
+use rand::RngExt;
+use slhdsa_c_rs::*;
+
+/// dudect threshold on `|t|` above which timing leakage is reported
+const T_THRESHOLD: f64 = 4.5;
+
+/// Percentiles at which measurements are cropped, to remove the long tail
+/// caused by interrupts and other system noise
+const PERCENTILES: [f64; 5] = [0.50, 0.75, 0.90, 0.95, 0.99];
+
+const DEFAULT_SAMPLES: usize = 2_000;
+const MSG: &[u8] = b"slhdsa-c-rs constant-time test";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Sign,
+    Keygen,
+}
+
+impl Operation {
+    fn name(self) -> &'static str {
+        match self {
+            Operation::Sign => "sign",
+            Operation::Keygen => "keygen",
+        }
+    }
+}
+
+/// Online Welch's t-test, using Welford's algorithm for each class
+#[derive(Debug, Default, Clone, Copy)]
+struct Welch {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        if self.n[0] < 2.0 || self.n[1] < 2.0 {
+            return 0.0;
+        }
+        let var0 = self.m2[0] / (self.n[0] - 1.0);
+        let var1 = self.m2[1] / (self.n[1] - 1.0);
+        let den = (var0 / self.n[0] + var1 / self.n[1]).sqrt();
+        if den == 0.0 {
+            return 0.0;
+        }
+        (self.mean[0] - self.mean[1]) / den
+    }
+}
+
+struct Row {
+    parameter_set: &'static str,
+    operation: Operation,
+    samples: usize,
+    max_t: f64,
+    mean_ns: [f64; 2],
+}
+
+impl Row {
+    fn verdict(&self) -> &'static str {
+        if self.max_t.abs() > T_THRESHOLD {
+            "**possible leakage**"
+        } else {
+            "no leakage detected"
+        }
+    }
+}
+
+/// Time a single execution of `operation` with the given secret input
+fn measure<P: ParameterSet>(operation: Operation, secret: &[u8]) -> f64 {
+    match operation {
+        Operation::Sign => {
+            let sk = SigningKey::<P>::try_from(secret).expect("Invalid secret key");
+            let start = Instant::now();
+            let sig = sk.try_sign_with_ctx(MSG, b"");
+            let elapsed = start.elapsed();
+            std::hint::black_box(sig).expect("Signing failed");
+            elapsed.as_secs_f64() * 1e9
+        }
+        Operation::Keygen => {
+            let n = P::VERIFYING_KEY_LEN / 2;
+            let (sk_seed, rest) = secret.split_at(n);
+            let (sk_prf, pk_seed) = rest.split_at(n);
+            let start = Instant::now();
+            let keys = keygen_from_seeds::<P>(sk_seed, sk_prf, pk_seed);
+            let elapsed = start.elapsed();
+            std::hint::black_box(keys).expect("Keygen failed");
+            elapsed.as_secs_f64() * 1e9
+        }
+    }
+}
+
+fn run<P: ParameterSet>(operation: Operation, samples: usize) -> Row {
+    let mut rng = rand::rng();
+    let secret_len = match operation {
+        Operation::Sign => P::SIGNING_KEY_LEN,
+        Operation::Keygen => 3 * (P::VERIFYING_KEY_LEN / 2),
+    };
+    let mut random_secret = || {
+        let mut secret = vec![0u8; secret_len];
+        rng.fill(&mut secret[..]);
+        secret
+    };
+    let fixed = random_secret();
+
+    // Prepare all inputs ahead of time, so that only the operation is timed
+    let inputs: Vec<(usize, Vec<u8>)> = (0..samples)
+        .map(|_| {
+            let class = usize::from(rand::rng().random::<bool>());
+            let secret = if class == 0 {
+                fixed.clone()
+            } else {
+                random_secret()
+            };
+            (class, secret)
+        })
+        .collect();
+
+    let measurements: Vec<(usize, f64)> = inputs
+        .iter()
+        .map(|(class, secret)| (*class, measure::<P>(operation, secret)))
+        .collect();
+
+    // Discard the first 10% as warm-up, and use them to pick the cropping
+    // thresholds
+    let (warmup, measurements) = measurements.split_at(samples / 10);
+    let mut sorted: Vec<f64> = warmup.iter().map(|(_, x)| *x).collect();
+    sorted.sort_by(f64::total_cmp);
+    let thresholds: Vec<f64> = if sorted.is_empty() {
+        Vec::new()
+    } else {
+        PERCENTILES
+            .iter()
+            .map(|p| sorted[((sorted.len() - 1) as f64 * p) as usize])
+            .collect()
+    };
+
+    let mut tests = vec![Welch::default(); thresholds.len() + 1];
+    for &(class, x) in measurements {
+        tests[0].push(class, x);
+        for (test, threshold) in tests[1..].iter_mut().zip(&thresholds) {
+            if x < *threshold {
+                test.push(class, x);
+            }
+        }
+    }
+
+    let max_t = tests
+        .iter()
+        .map(Welch::t)
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or(0.0);
+
+    Row {
+        parameter_set: P::NAME,
+        operation,
+        samples: measurements.len(),
+        max_t,
+        mean_ns: tests[0].mean,
+    }
+}
+
+macro_rules! for_each_parameter_set {
+    ($f:ident) => {
+        [
+            (
+                SLH_DSA_SHAKE_128s::NAME,
+                $f::<SLH_DSA_SHAKE_128s> as fn(Operation, usize) -> Row,
+            ),
+            (SLH_DSA_SHAKE_128f::NAME, $f::<SLH_DSA_SHAKE_128f>),
+            (SLH_DSA_SHAKE_192s::NAME, $f::<SLH_DSA_SHAKE_192s>),
+            (SLH_DSA_SHAKE_192f::NAME, $f::<SLH_DSA_SHAKE_192f>),
+            (SLH_DSA_SHAKE_256s::NAME, $f::<SLH_DSA_SHAKE_256s>),
+            (SLH_DSA_SHAKE_256f::NAME, $f::<SLH_DSA_SHAKE_256f>),
+            (SLH_DSA_SHA2_128s::NAME, $f::<SLH_DSA_SHA2_128s>),
+            (SLH_DSA_SHA2_128f::NAME, $f::<SLH_DSA_SHA2_128f>),
+            (SLH_DSA_SHA2_192s::NAME, $f::<SLH_DSA_SHA2_192s>),
+            (SLH_DSA_SHA2_192f::NAME, $f::<SLH_DSA_SHA2_192f>),
+            (SLH_DSA_SHA2_256s::NAME, $f::<SLH_DSA_SHA2_256s>),
+            (SLH_DSA_SHA2_256f::NAME, $f::<SLH_DSA_SHA2_256f>),
+        ]
+    };
+}
+
+struct Options {
+    samples: usize,
+    params: Vec<String>,
+    operations: Vec<Operation>,
+    output: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        samples: DEFAULT_SAMPLES,
+        params: Vec::new(),
+        operations: Vec::new(),
+        output: None,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {arg}"));
+        match arg.as_str() {
+            "--samples" => {
+                options.samples = value()?
+                    .parse()
+                    .map_err(|e| format!("Invalid --samples: {e}"))?;
+            }
+            "--param" => options.params.push(value()?),
+            "--op" => options.operations.push(match value()?.as_str() {
+                "sign" => Operation::Sign,
+                "keygen" => Operation::Keygen,
+                other => return Err(format!("Unknown operation: {other}")),
+            }),
+            "--output" => options.output = Some(value()?),
+            other => return Err(format!("Unknown argument: {other}")),
+        }
+    }
+    if options.operations.is_empty() {
+        options.operations = vec![Operation::Sign, Operation::Keygen];
+    }
+
+    Ok(options)
+}
+
+/// CPU model name, from `/proc/cpuinfo` where available
+fn cpu_model() -> String {
+    std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find(|line| line.starts_with("model name"))
+                .and_then(|line| line.split_once(':'))
+                .map(|(_, model)| model.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/// First line of `$var --version` (or `default --version`), which is
+/// assumed to be the compiler the example was built with
+fn tool_version(var: &str, default: &str) -> String {
+    let tool = std::env::var(var).unwrap_or_else(|_| default.into());
+    std::process::Command::new(&tool)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .map(|line| format!("`{}`", line.trim()))
+        })
+        .unwrap_or_else(|| "unknown".into())
+}
+
+fn report(options: &Options, rows: &[Row]) -> String {
+    let mut out = String::new();
+    writeln!(out, "# Constant-time report").unwrap();
+    writeln!(out).unwrap();
+    let mut args = format!("--samples {}", options.samples);
+    for param in &options.params {
+        write!(args, " --param {param}").unwrap();
+    }
+    if options.operations.len() == 1 {
+        write!(args, " --op {}", options.operations[0].name()).unwrap();
+    }
+    writeln!(
+        out,
+        "Generated with `cargo run --release --example dudect -- {args}`."
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "- Target: `{}-{}`",
+        std::env::consts::ARCH,
+        std::env::consts::OS
+    )
+    .unwrap();
+    writeln!(out, "- CPU: {}", cpu_model()).unwrap();
+    writeln!(out, "- Rust compiler: {}", tool_version("RUSTC", "rustc")).unwrap();
+    writeln!(out, "- C compiler: {}", tool_version("CC", "cc")).unwrap();
+    writeln!(out, "- Backend: {}", build_info::BUILD_INFO).unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "Class 0 uses a fixed secret, class 1 fresh random secrets. \
+         Leakage is reported when `|t| > {T_THRESHOLD}`."
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "| Parameter set | Operation | Samples | Mean class 0 (µs) | Mean class 1 (µs) | max \\|t\\| | Verdict |"
+    )
+    .unwrap();
+    writeln!(out, "|---|---|---:|---:|---:|---:|---|").unwrap();
+    for row in rows {
+        writeln!(
+            out,
+            "| {} | {} | {} | {:.1} | {:.1} | {:.2} | {} |",
+            row.parameter_set,
+            row.operation.name(),
+            row.samples,
+            row.mean_ns[0] / 1e3,
+            row.mean_ns[1] / 1e3,
+            row.max_t.abs(),
+            row.verdict()
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn main() -> Result<(), String> {
+    let options = parse_args()?;
+
+    let mut rows = Vec::new();
+    for (name, f) in for_each_parameter_set!(run) {
+        if !options.params.is_empty() && !options.params.iter().any(|p| p == name) {
+            continue;
+        }
+        for &operation in &options.operations {
+            eprintln!("Testing {name} {}...", operation.name());
+            let row = f(operation, options.samples);
+            eprintln!("  max |t| = {:.2}: {}", row.max_t.abs(), row.verdict());
+            rows.push(row);
+        }
+    }
+    if rows.is_empty() {
+        return Err("No parameter set selected".into());
+    }
+
+    let report = report(&options, &rows);
+    match &options.output {
+        Some(path) => std::fs::write(path, report).map_err(|e| format!("{path}: {e}"))?,
+        None => print!("{report}"),
+    }
+
+    if rows.iter().any(|row| row.max_t.abs() > T_THRESHOLD) {
+        return Err("Possible timing leakage detected".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Generate cross-implementation known-answer test files.
+//!
+//! Writes `<NAME>.rsp` files in the layout replayed by `tests/test_kat.rs`
+//! (that of the NIST `PQCgenKAT_sign` tool), with the expected values
+//! computed by the RustCrypto [`slh-dsa`] crate rather than by `slhdsa-c`.
+//! This is how the files of `tests/kat/cross/` were produced (see
+//! `tests/kat/README.md`):
+//!
+//! ```sh
+//! cargo run --release --features kat --example gen_kat -- --output tests/kat/cross
+//! ```
+//!
+//! As in `PQCgenKAT_sign`, entry `i` (from 0) has a `seed` and a message of
+//! `33 * (i + 1)` bytes drawn from the `CTR_DRBG` seeded with `00 01 .. 2F`.
+//! The keygen seeds and `addrnd` are then drawn from the `CTR_DRBG` seeded
+//! with `seed`, and the message is signed with an empty context.
+//!
+//! `--count` sets the number of entries (1 by default). Without `--param`, a
+//! file is written for every parameter set.
+//!
+//! [`slh-dsa`]: https://crates.io/crates/slh-dsa
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use slhdsa_c_rs::kat::{KatDrbg, SEED_LEN};
+use slhdsa_c_rs::*;
+
+/// Write the `.rsp` file of `P` into `dir`, computed with `Q`
+fn generate<P: ParameterSet, Q: slh_dsa::ParameterSet>(
+    count: usize,
+    dir: &Path,
+) -> Result<(), String> {
+    let name = P::NAME;
+    let entropy_input: [u8; SEED_LEN] = core::array::from_fn(|i| i as u8);
+    let mut drbg = KatDrbg::new(&entropy_input, None);
+    let n = P::VERIFYING_KEY_LEN / 2;
+
+    let mut rsp = format!("# {name}\n");
+    for i in 0..count {
+        let mut seed = [0u8; SEED_LEN];
+        drbg.fill(&mut seed);
+        let mut msg = vec![0u8; 33 * (i + 1)];
+        drbg.fill(&mut msg);
+
+        let mut entry_drbg = KatDrbg::new(&seed, None);
+        let mut seeds = vec![0u8; 3 * n];
+        entry_drbg.fill(&mut seeds);
+        let mut addrnd = vec![0u8; n];
+        entry_drbg.fill(&mut addrnd);
+
+        let sk = slh_dsa::SigningKey::<Q>::slh_keygen_internal(
+            &seeds[..n],
+            &seeds[n..2 * n],
+            &seeds[2 * n..],
+        );
+        let sig = sk
+            .try_sign_with_context(&msg, &[], Some(&addrnd))
+            .map_err(|e| format!("{name}: signing failed: {e}"))?;
+        let sm = [&sig.to_bytes()[..], &msg].concat();
+
+        let _ = write!(
+            rsp,
+            "\ncount = {i}\nseed = {}\nmlen = {}\nmsg = {}\npk = {}\nsk = {}\nsmlen = {}\nsm = {}\n",
+            hex::encode_upper(seed),
+            msg.len(),
+            hex::encode_upper(&msg),
+            hex::encode_upper(sk.verifying_key().to_bytes()),
+            hex::encode_upper(sk.to_bytes()),
+            sm.len(),
+            hex::encode_upper(&sm),
+        );
+    }
+
+    let path = dir.join(format!("{name}.rsp"));
+    std::fs::write(&path, rsp).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn main() -> Result<(), String> {
+    let mut params = Vec::new();
+    let mut count = 1;
+    let mut output = PathBuf::from(".");
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {arg}"));
+        match arg.as_str() {
+            "--count" => {
+                count = value()?
+                    .parse()
+                    .map_err(|e| format!("Invalid --count: {e}"))?;
+            }
+            "--param" => params.push(value()?),
+            "--output" => output = PathBuf::from(value()?),
+            other => return Err(format!("Unknown argument: {other}")),
+        }
+    }
+
+    let mut generated = 0;
+    macro_rules! generate_each {
+        ( $( #[cfg($cfg:meta)] $ours:ident => $theirs:ty ),+ $(,)? ) => {
+            $(
+                #[cfg($cfg)]
+                if params.is_empty() || params.iter().any(|p| p == $ours::NAME) {
+                    eprintln!("Generating {}...", $ours::NAME);
+                    generate::<$ours, $theirs>(count, &output)?;
+                    generated += 1;
+                }
+            )+
+        };
+    }
+    slhdsa_c_rs::for_each_slh_dsa_pair!(generate_each);
+
+    if generated == 0 {
+        return Err("No parameter set selected".into());
+    }
+    Ok(())
+}
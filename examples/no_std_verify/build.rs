@@ -0,0 +1,33 @@
+//! Generate the test vector on the host, and select the linker script of the
+//! target.
+
+use std::path::PathBuf;
+
+use slhdsa_c_rs::*;
+
+/// Must match `src/main.rs`
+const MSG: &[u8] = b"slhdsa-c-rs on bare metal";
+const CTX: &[u8] = b"no_std";
+
+fn main() {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+
+    // n = 16 bytes for SLH-DSA-SHAKE-128s
+    let (sk, vk) = keygen_from_seeds::<SLH_DSA_SHAKE_128s>(&[1u8; 16], &[2u8; 16], &[3u8; 16])
+        .expect("Keygen failed");
+    let sig = sk.try_sign_with_ctx(MSG, CTX).expect("Signing failed");
+    std::fs::write(out_dir.join("vk.bin"), vk.as_bytes()).unwrap();
+    std::fs::write(out_dir.join("sig.bin"), sig.as_bytes()).unwrap();
+
+    let memory = match std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() {
+        Ok("arm") => Some("memory-cortex-m.x"),
+        Ok("riscv32") => Some("memory-riscv.x"),
+        _ => None,
+    };
+    if let Some(memory) = memory {
+        std::fs::copy(memory, out_dir.join("memory.x")).unwrap();
+        println!("cargo:rustc-link-search={}", out_dir.display());
+        println!("cargo:rerun-if-changed={memory}");
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,6 @@
+/* TI LM3S6965 (QEMU `lm3s6965evb` machine) */
+MEMORY
+{
+  FLASH : ORIGIN = 0x00000000, LENGTH = 256K
+  RAM : ORIGIN = 0x20000000, LENGTH = 64K
+}
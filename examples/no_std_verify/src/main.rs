@@ -0,0 +1,94 @@
+//! SLH-DSA verification on bare-metal targets.
+//!
+//! Verifies a signature generated on the host by `build.rs`, checks that a
+//! corrupted copy is rejected, and reports the result through semihosting.
+
+#![no_std]
+#![no_main]
+
+use slhdsa_c_rs::*;
+
+type P = SLH_DSA_SHAKE_128s;
+
+const MSG: &[u8] = b"slhdsa-c-rs on bare metal";
+const CTX: &[u8] = b"no_std";
+
+static VK: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/vk.bin"));
+static SIG: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/sig.bin"));
+
+/// Run the checks, returning the name of the first failing one
+fn run() -> Result<(), &'static str> {
+    let vk = VerifyingKey::<P>::try_from(VK).map_err(|_| "decoding the verifying key")?;
+    let sig = Signature::<P>::try_from(SIG).map_err(|_| "decoding the signature")?;
+    vk.verify_with_ctx(MSG, CTX, &sig)
+        .map_err(|_| "verifying a valid signature")?;
+
+    let mut corrupted = [0u8; P::SIGNATURE_LEN];
+    corrupted.copy_from_slice(SIG);
+    corrupted[P::SIGNATURE_LEN / 2] ^= 1;
+    let corrupted =
+        Signature::<P>::try_from(&corrupted[..]).map_err(|_| "decoding the corrupted signature")?;
+    if vk.verify_with_ctx(MSG, CTX, &corrupted).is_ok() {
+        return Err("rejecting a corrupted signature");
+    }
+    if vk.verify_with_ctx(b"another message", CTX, &sig).is_ok() {
+        return Err("rejecting a different message");
+    }
+    Ok(())
+}
+
+#[cfg(target_arch = "arm")]
+mod platform {
+    use cortex_m_rt::entry;
+    use cortex_m_semihosting::{debug, hprintln};
+
+    #[entry]
+    fn main() -> ! {
+        match super::run() {
+            Ok(()) => {
+                hprintln!("{}: OK", super::P::NAME);
+                debug::exit(debug::EXIT_SUCCESS);
+            }
+            Err(check) => {
+                hprintln!("{}: FAILED {}", super::P::NAME, check);
+                debug::exit(debug::EXIT_FAILURE);
+            }
+        }
+        loop {}
+    }
+
+    #[panic_handler]
+    fn panic(info: &core::panic::PanicInfo) -> ! {
+        hprintln!("{}", info);
+        debug::exit(debug::EXIT_FAILURE);
+        loop {}
+    }
+}
+
+#[cfg(target_arch = "riscv32")]
+mod platform {
+    use riscv_rt::entry;
+    use riscv_semihosting::{debug, hprintln};
+
+    #[entry]
+    fn main() -> ! {
+        match super::run() {
+            Ok(()) => {
+                hprintln!("{}: OK", super::P::NAME);
+                debug::exit(debug::EXIT_SUCCESS);
+            }
+            Err(check) => {
+                hprintln!("{}: FAILED {}", super::P::NAME, check);
+                debug::exit(debug::EXIT_FAILURE);
+            }
+        }
+        loop {}
+    }
+
+    #[panic_handler]
+    fn panic(info: &core::panic::PanicInfo) -> ! {
+        hprintln!("{}", info);
+        debug::exit(debug::EXIT_FAILURE);
+        loop {}
+    }
+}
@@ -0,0 +1,27 @@
+//! Helpers shared by the fuzz targets.
+
+/// Number of parameter sets covered by [`dispatch`].
+pub const PARAMETER_SETS: u8 = 12;
+
+/// Call the generic function `$f::<P>($args...)` for the parameter set `P`
+/// selected by `$selector`.
+macro_rules! dispatch {
+    ($selector:expr, $f:ident ( $($arg:expr),* $(,)? )) => {{
+        use slhdsa_c_rs::*;
+        match $selector % $crate::common::PARAMETER_SETS {
+            0 => $f::<SLH_DSA_SHAKE_128s>($($arg),*),
+            1 => $f::<SLH_DSA_SHAKE_128f>($($arg),*),
+            2 => $f::<SLH_DSA_SHAKE_192s>($($arg),*),
+            3 => $f::<SLH_DSA_SHAKE_192f>($($arg),*),
+            4 => $f::<SLH_DSA_SHAKE_256s>($($arg),*),
+            5 => $f::<SLH_DSA_SHAKE_256f>($($arg),*),
+            6 => $f::<SLH_DSA_SHA2_128s>($($arg),*),
+            7 => $f::<SLH_DSA_SHA2_128f>($($arg),*),
+            8 => $f::<SLH_DSA_SHA2_192s>($($arg),*),
+            9 => $f::<SLH_DSA_SHA2_192f>($($arg),*),
+            10 => $f::<SLH_DSA_SHA2_256s>($($arg),*),
+            _ => $f::<SLH_DSA_SHA2_256f>($($arg),*),
+        }
+    }};
+}
+pub(crate) use dispatch;
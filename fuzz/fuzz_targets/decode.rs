@@ -0,0 +1,42 @@
+//! Feed arbitrary bytes to the `TryFrom<&[u8]>` decoders of `SigningKey`,
+//! `VerifyingKey` and `Signature`.
+//!
+//! Decoding must succeed if and only if the length is the expected one, and
+//! decoded values must encode back to the exact input bytes.
+
+#![no_main]
+
+mod common;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use slhdsa_c_rs::*;
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    parameter_set: u8,
+    bytes: &'a [u8],
+}
+
+fn check_decode<T, E>(bytes: &[u8], expected_len: usize)
+where
+    T: for<'b> TryFrom<&'b [u8], Error = E> + AsBytes,
+{
+    match T::try_from(bytes) {
+        Ok(decoded) => {
+            assert_eq!(bytes.len(), expected_len);
+            assert_eq!(decoded.as_bytes(), bytes);
+        }
+        Err(_) => assert_ne!(bytes.len(), expected_len),
+    }
+}
+
+fn fuzz_decode<P: ParameterSet>(bytes: &[u8]) {
+    check_decode::<SigningKey<P>, _>(bytes, P::SIGNING_KEY_LEN);
+    check_decode::<VerifyingKey<P>, _>(bytes, P::VERIFYING_KEY_LEN);
+    check_decode::<Signature<P>, _>(bytes, P::SIGNATURE_LEN);
+}
+
+fuzz_target!(|input: Input<'_>| {
+    common::dispatch!(input.parameter_set, fuzz_decode(input.bytes));
+});
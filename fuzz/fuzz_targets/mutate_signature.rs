@@ -0,0 +1,165 @@
+//! Structure-aware mutation of valid signatures.
+//!
+//! For each parameter set, a valid `(vk, msg, ctx, sig)` fixture is
+//! generated once from fixed seeds. The fuzzer then applies a sequence of
+//! mutations to the signature (targeting either the randomizer `R` or the
+//! FORS/hypertree body), to its length, to the message and to the context.
+//!
+//! Verification must succeed if and only if the mutated inputs are exactly
+//! the original ones.
+
+#![no_main]
+
+mod common;
+
+use std::sync::OnceLock;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use slhdsa_c_rs::*;
+
+const MSG: &[u8] = b"slhdsa-c-rs structure-aware fuzzing";
+const CTX: &[u8] = b"fuzz";
+
+/// The part of the signature a mutation is applied to
+#[derive(Arbitrary, Debug, Clone, Copy)]
+enum Region {
+    /// The first `n` bytes: the randomizer `R`
+    Randomizer,
+    /// The FORS signature and the hypertree signature
+    Body,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Mutation {
+    FlipBit {
+        region: Region,
+        offset: u16,
+        bit: u8,
+    },
+    SetByte {
+        region: Region,
+        offset: u16,
+        value: u8,
+    },
+    Truncate {
+        len: u16,
+    },
+    Extend {
+        bytes: Vec<u8>,
+    },
+    SetMsgByte {
+        offset: u16,
+        value: u8,
+    },
+    ExtendMsg {
+        bytes: Vec<u8>,
+    },
+    TruncateMsg {
+        len: u16,
+    },
+    ReplaceCtx {
+        ctx: Vec<u8>,
+    },
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    parameter_set: u8,
+    mutations: Vec<Mutation>,
+}
+
+struct Fixture {
+    vk: Vec<u8>,
+    sig: Vec<u8>,
+}
+
+static FIXTURES: [OnceLock<Fixture>; common::PARAMETER_SETS as usize] =
+    [const { OnceLock::new() }; common::PARAMETER_SETS as usize];
+
+fn fixture<P: ParameterSet>() -> Fixture {
+    let n = P::VERIFYING_KEY_LEN / 2;
+    let (sk, vk) =
+        keygen_from_seeds::<P>(&vec![1u8; n], &vec![2u8; n], &vec![3u8; n]).expect("Keygen failed");
+    let sig = sk.try_sign_with_ctx(MSG, CTX).expect("Signing failed");
+
+    Fixture {
+        vk: vk.as_bytes().to_vec(),
+        sig: sig.as_bytes().to_vec(),
+    }
+}
+
+/// Map `offset` to an index within `region` of `sig`, if not empty
+fn region_index(sig: &[u8], n: usize, region: Region, offset: u16) -> Option<usize> {
+    let (start, end) = match region {
+        Region::Randomizer => (0, n.min(sig.len())),
+        Region::Body => (n.min(sig.len()), sig.len()),
+    };
+    (start < end).then(|| start + usize::from(offset) % (end - start))
+}
+
+fn apply(mutation: &Mutation, n: usize, sig: &mut Vec<u8>, msg: &mut Vec<u8>, ctx: &mut Vec<u8>) {
+    match mutation {
+        Mutation::FlipBit {
+            region,
+            offset,
+            bit,
+        } => {
+            if let Some(i) = region_index(sig, n, *region, *offset) {
+                sig[i] ^= 1 << (bit % 8);
+            }
+        }
+        Mutation::SetByte {
+            region,
+            offset,
+            value,
+        } => {
+            if let Some(i) = region_index(sig, n, *region, *offset) {
+                sig[i] = *value;
+            }
+        }
+        Mutation::Truncate { len } => sig.truncate(usize::from(*len)),
+        Mutation::Extend { bytes } => sig.extend_from_slice(bytes),
+        Mutation::SetMsgByte { offset, value } => {
+            if !msg.is_empty() {
+                let i = usize::from(*offset) % msg.len();
+                msg[i] = *value;
+            }
+        }
+        Mutation::ExtendMsg { bytes } => msg.extend_from_slice(bytes),
+        Mutation::TruncateMsg { len } => msg.truncate(usize::from(*len)),
+        Mutation::ReplaceCtx { ctx: new_ctx } => new_ctx.clone_into(ctx),
+    }
+}
+
+fn fuzz_mutate<P: ParameterSet>(index: usize, input: &Input) {
+    let fixture = FIXTURES[index].get_or_init(fixture::<P>);
+    let n = P::VERIFYING_KEY_LEN / 2;
+
+    let mut sig = fixture.sig.clone();
+    let mut msg = MSG.to_vec();
+    let mut ctx = CTX.to_vec();
+    for mutation in &input.mutations {
+        apply(mutation, n, &mut sig, &mut msg, &mut ctx);
+    }
+
+    let unchanged = sig == fixture.sig && msg == MSG && ctx == CTX;
+
+    let Ok(decoded) = Signature::<P>::try_from(&sig[..]) else {
+        assert_ne!(sig.len(), P::SIGNATURE_LEN);
+        return;
+    };
+    let vk = VerifyingKey::<P>::try_from(&fixture.vk[..]).expect("Invalid fixture");
+
+    let ret = vk.verify_with_ctx(&msg, &ctx, &decoded);
+    assert_eq!(
+        ret.is_ok(),
+        unchanged,
+        "Verification result does not match (unchanged: {unchanged})"
+    );
+}
+
+fuzz_target!(|input: Input| {
+    let index = usize::from(input.parameter_set % common::PARAMETER_SETS);
+    common::dispatch!(input.parameter_set, fuzz_mutate(index, &input));
+});
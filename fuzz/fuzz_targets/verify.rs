@@ -0,0 +1,44 @@
+//! Feed arbitrary verifying keys, messages, contexts and signatures to
+//! `slh_verify` through `VerifyingKey::verify_with_ctx()`.
+//!
+//! Inputs that cannot be decoded are still exercised by padding or
+//! truncating them to the expected length. Contexts longer than 255 bytes
+//! must always be rejected, and random signatures must never verify.
+
+#![no_main]
+
+mod common;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use slhdsa_c_rs::*;
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    parameter_set: u8,
+    vk: &'a [u8],
+    sig: &'a [u8],
+    ctx: &'a [u8],
+    msg: &'a [u8],
+}
+
+/// Pad with zeroes or truncate `bytes` to `len`
+fn resize(bytes: &[u8], len: usize) -> Vec<u8> {
+    let mut out = bytes.to_vec();
+    out.resize(len, 0);
+    out
+}
+
+fn fuzz_verify<P: ParameterSet>(input: &Input<'_>) {
+    let vk = VerifyingKey::<P>::try_from(&resize(input.vk, P::VERIFYING_KEY_LEN)[..])
+        .expect("Decoding a verifying key of the right length failed");
+    let sig = Signature::<P>::try_from(&resize(input.sig, P::SIGNATURE_LEN)[..])
+        .expect("Decoding a signature of the right length failed");
+
+    let ret = vk.verify_with_ctx(input.msg, input.ctx, &sig);
+    assert!(ret.is_err(), "Arbitrary signature verified");
+}
+
+fuzz_target!(|input: Input<'_>| {
+    common::dispatch!(input.parameter_set, fuzz_verify(&input));
+});
@@ -0,0 +1,156 @@
+//! How the `slhdsa-c` backend was built.
+//!
+//! The C library can be instrumented with sanitizers (the `sanitize` feature
+//! or the `SLHDSA_C_SANITIZE` environment variable) for test runs, or
+//! compiled with hardening flags (the `hardening` feature or the
+//! `SLHDSA_C_HARDENING` environment variable) for production builds.
+//! [`BUILD_INFO`] reports which configuration was used, e.g. to log it at
+//! startup:
+//!
+//! ```rust
+//! println!("{}", slhdsa_c_rs::build_info::BUILD_INFO);
+//! ```
+
+use core::fmt;
+
+/// Where the C library comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Compiled from the bundled `slhdsa-c` submodule
+    Bundled,
+    /// Compiled from the sources in `SLHDSA_C_SRC_DIR`
+    Custom,
+    /// A prebuilt system library (the `system` feature)
+    System,
+}
+
+impl Source {
+    const fn parse(s: &str) -> Self {
+        match s.as_bytes() {
+            b"custom" => Source::Custom,
+            b"system" => Source::System,
+            _ => Source::Bundled,
+        }
+    }
+
+    /// Name of the source, as reported by the build script
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Source::Bundled => "bundled",
+            Source::Custom => "custom",
+            Source::System => "system",
+        }
+    }
+}
+
+/// Build configuration of the C library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Where the C library comes from
+    pub source: Source,
+    /// Comma-separated list of the sanitizers the C code is instrumented
+    /// with (empty if none)
+    pub sanitizers: &'static str,
+    /// Whether the C code is compiled with hardening flags (stack
+    /// protector, `_FORTIFY_SOURCE`, control-flow protection)
+    pub hardening: bool,
+    /// Whether the C code calls the Rust Keccak permutation (the
+    /// `rust-keccak` feature)
+    pub rust_keccak: bool,
+    /// Whether the C code calls the Rust SHA-2 compression functions (the
+    /// `rust-sha2` feature)
+    pub rust_sha2: bool,
+}
+
+impl BuildInfo {
+    /// Iterate over the sanitizers the C code is instrumented with
+    pub fn sanitizers(&self) -> impl Iterator<Item = &'static str> {
+        self.sanitizers.split(',').filter(|s| !s.is_empty())
+    }
+
+    /// Whether the C code is instrumented with `sanitizer`
+    #[must_use]
+    pub fn is_sanitized_with(&self, sanitizer: &str) -> bool {
+        self.sanitizers().any(|s| s == sanitizer)
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slhdsa-c ({}", self.source.as_str())?;
+        if !self.sanitizers.is_empty() {
+            write!(f, ", sanitizers: {}", self.sanitizers)?;
+        }
+        if self.hardening {
+            write!(f, ", hardened")?;
+        }
+        if self.rust_keccak {
+            write!(f, ", rust-keccak")?;
+        }
+        if self.rust_sha2 {
+            write!(f, ", rust-sha2")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Build configuration of the linked C library
+pub const BUILD_INFO: BuildInfo = BuildInfo {
+    source: Source::parse(env!("SLHDSA_C_RS_SOURCE")),
+    sanitizers: env!("SLHDSA_C_RS_SANITIZERS"),
+    hardening: matches!(env!("SLHDSA_C_RS_HARDENING").as_bytes(), b"1"),
+    rust_keccak: matches!(env!("SLHDSA_C_RS_RUST_KECCAK").as_bytes(), b"1"),
+    rust_sha2: matches!(env!("SLHDSA_C_RS_RUST_SHA2").as_bytes(), b"1"),
+};
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_build_info_matches_features() {
+        // The environment can enable more than the features
+        let info = &BUILD_INFO;
+        if cfg!(feature = "sanitize") {
+            assert!(info.is_sanitized_with("address"));
+            assert!(info.is_sanitized_with("undefined"));
+        }
+        if cfg!(feature = "hardening") {
+            assert!(info.hardening);
+        }
+        assert_eq!(info.rust_keccak, cfg!(feature = "rust-keccak"));
+        assert_eq!(info.rust_sha2, cfg!(feature = "rust-sha2"));
+        assert_eq!(info.source == Source::System, cfg!(feature = "system"));
+    }
+
+    #[test]
+    fn test_build_info_display() {
+        let info = BuildInfo {
+            source: Source::Custom,
+            sanitizers: "address,undefined",
+            hardening: true,
+            rust_keccak: true,
+            rust_sha2: true,
+        };
+        assert_eq!(
+            info.to_string(),
+            "slhdsa-c (custom, sanitizers: address,undefined, hardened, rust-keccak, rust-sha2)"
+        );
+        assert_eq!(info.sanitizers().count(), 2);
+        assert!(!info.is_sanitized_with("thread"));
+
+        let info = BuildInfo {
+            source: Source::Bundled,
+            sanitizers: "",
+            hardening: false,
+            rust_keccak: false,
+            rust_sha2: false,
+        };
+        assert_eq!(info.to_string(), "slhdsa-c (bundled)");
+        assert_eq!(info.sanitizers().count(), 0);
+    }
+}
@@ -34,8 +34,37 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 #![allow(missing_docs)]
+// The documentation of the bindings is copied from the C header
+#![allow(clippy::doc_markdown)]
 
 pub use c_types::*;
 pub use core::ffi as c_types;
 
+/// Opaque parameter set of `slhdsa-c`.
+///
+/// Parameter sets are only ever used through references to the
+/// `slh_dsa_*` statics defined by the C library, so their layout (which
+/// depends on the target) is not exposed. This keeps the pre-generated
+/// bindings target independent.
+///
+/// Until 0.0.5, its fields were generated from the header and public: see
+/// the migration notes in the README.
+#[repr(C)]
+pub struct slh_param_s {
+    _data: [u8; 0],
+    _marker: core::marker::PhantomData<(*mut u8, core::marker::PhantomPinned)>,
+}
+
+/// Alias matching the `slh_param_t` typedef of `slhdsa-c`.
+pub type slh_param_t = slh_param_s;
+
+// Bindings generated at build time, with the `bindgen` feature
+#[cfg(feature = "bindgen")]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+// Pre-generated bindings for the pinned `slhdsa-c` revision: regenerate
+// them by building with the `bindgen` feature and copying
+// `$OUT_DIR/bindings.rs` over `src/ffi/bindings.rs`
+// (`tests/test_bindings_drift.rs` checks that they are in sync).
+#[cfg(not(feature = "bindgen"))]
+include!("ffi/bindings.rs");
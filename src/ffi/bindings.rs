@@ -0,0 +1,148 @@
+/* automatically generated by rust-bindgen 0.72.1 */
+
+unsafe extern "C" {
+    #[doc = " === SLH-DSA parameter sets"]
+    pub static slh_dsa_sha2_128s: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_shake_128s: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_sha2_128f: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_shake_128f: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_sha2_192s: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_shake_192s: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_sha2_192f: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_shake_192f: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_sha2_256s: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_shake_256s: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_sha2_256f: slh_param_t;
+}
+unsafe extern "C" {
+    pub static slh_dsa_shake_256f: slh_param_t;
+}
+unsafe extern "C" {
+    #[doc = " Return standard identifier string for parameter set *prm, or NULL."]
+    pub fn slh_alg_id(prm: *const slh_param_t) -> *const self::c_char;
+}
+unsafe extern "C" {
+    #[doc = " Return public (verification) key size in bytes for parameter set *prm."]
+    pub fn slh_pk_sz(prm: *const slh_param_t) -> usize;
+}
+unsafe extern "C" {
+    #[doc = " Return private (signing) key size in bytes for parameter set *prm."]
+    pub fn slh_sk_sz(prm: *const slh_param_t) -> usize;
+}
+unsafe extern "C" {
+    #[doc = " Return signature size in bytes for parameter set *prm."]
+    pub fn slh_sig_sz(prm: *const slh_param_t) -> usize;
+}
+unsafe extern "C" {
+    #[doc = " Generate an SLH-DSA key pair; needs an RBG (a \"randombytes()\" function)"]
+    pub fn slh_keygen(
+        sk: *mut u8,
+        pk: *mut u8,
+        rbg: ::core::option::Option<unsafe extern "C" fn(x: *mut u8, xlen: usize) -> self::c_int>,
+        prm: *const slh_param_t,
+    ) -> self::c_int;
+}
+unsafe extern "C" {
+    pub fn slh_keygen_internal(
+        sk: *mut u8,
+        pk: *mut u8,
+        sk_seed: *const u8,
+        sk_prf: *const u8,
+        pk_seed: *const u8,
+        prm: *const slh_param_t,
+    ) -> self::c_int;
+}
+unsafe extern "C" {
+    #[doc = " Generate an SLH-DSA signature."]
+    pub fn slh_sign_internal(
+        sig: *mut u8,
+        m: *const u8,
+        m_sz: usize,
+        sk: *const u8,
+        addrnd: *const u8,
+        prm: *const slh_param_t,
+    ) -> usize;
+}
+unsafe extern "C" {
+    pub fn slh_sign(
+        sig: *mut u8,
+        m: *const u8,
+        m_sz: usize,
+        ctx: *const u8,
+        ctx_sz: usize,
+        sk: *const u8,
+        addrnd: *const u8,
+        prm: *const slh_param_t,
+    ) -> usize;
+}
+unsafe extern "C" {
+    #[doc = " Verify an SLH-DSA signature. */\n/* return 0 on verification failure, 1 on success"]
+    pub fn slh_verify_internal(
+        m: *const u8,
+        m_sz: usize,
+        sig: *const u8,
+        sig_sz: usize,
+        pk: *const u8,
+        prm: *const slh_param_t,
+    ) -> self::c_int;
+}
+unsafe extern "C" {
+    pub fn slh_verify(
+        m: *const u8,
+        m_sz: usize,
+        sig: *const u8,
+        sig_sz: usize,
+        ctx: *const u8,
+        ctx_sz: usize,
+        pk: *const u8,
+        prm: *const slh_param_t,
+    ) -> self::c_int;
+}
+unsafe extern "C" {
+    #[doc = " === Generates a pre-hash SLH-DSA signature. */\n/* Algorithm 23: Algorithm 23 hash_slh_sign(M, ctx, PH, SK)"]
+    pub fn hash_slh_sign(
+        sig: *mut u8,
+        m: *const u8,
+        m_sz: usize,
+        ctx: *const u8,
+        ctx_sz: usize,
+        ph: *const self::c_char,
+        sk: *const u8,
+        addrnd: *const u8,
+        prm: *const slh_param_t,
+    ) -> usize;
+}
+unsafe extern "C" {
+    #[doc = " === Verifies a pre-hash SLH-DSA signature. */\n/* Algorithm 25: hash_slh_verify(M, SIG, ctx, PH, PK)"]
+    pub fn hash_slh_verify(
+        m: *const u8,
+        m_sz: usize,
+        sig: *const u8,
+        sig_sz: usize,
+        ctx: *const u8,
+        ctx_sz: usize,
+        ph: *const self::c_char,
+        pk: *const u8,
+        prm: *const slh_param_t,
+    ) -> self::c_int;
+}
@@ -0,0 +1,174 @@
+//! Rust implementations of the hash primitives of the C library.
+//!
+//! Nearly all the time of SLH-DSA is spent in the hash primitives, which
+//! `slhdsa-c` implements in portable C:
+//!
+//! - with the `rust-keccak` feature, the C code calls the Keccak-f\[1600\]
+//!   permutation of the [`keccak`] crate instead, for the SHAKE parameter
+//!   sets and the SHAKE pre-hashes. The crate has optimized implementations
+//!   for some targets (e.g., the `ARMv8` SHA-3 extensions with its `asm`
+//!   feature);
+//! - with the `rust-sha2` feature, the C code calls the SHA-256 and
+//!   SHA-512 compression functions of the [`sha2`] crate instead, for the
+//!   SHA-2 parameter sets and the SHA-2 pre-hashes. The crate uses the
+//!   SHA extensions of x86 and `AArch64` CPUs when they are available.
+//!
+//! The backend is selected at build time, for the whole library, and
+//! reported by [`BUILD_INFO`](crate::build_info::BUILD_INFO). The C
+//! implementations are still compiled, under other names: the tests of this
+//! module check that both implementations of each primitive agree, and the
+//! KAT and differential tests check the keys and signatures when run with
+//! these features. `benches/hash_backend.rs` compares builds with and
+//! without them.
+//!
+//! Without any parameter set (nor pre-hash) of a hash family, the C library
+//! does not use its primitives at all, and their backend has no effect.
+
+#[cfg(all(test, slhdsa_c_keccak))]
+unsafe extern "C" {
+    /// The C permutation, renamed by the build script
+    fn slhdsa_c_keccak_f1600(state: *mut u64);
+}
+
+/// The permutation called by the C library, in place of its own (which the
+/// build script renames the calls of)
+///
+/// # Safety
+///
+/// `state` must point to 25 valid and aligned `u64`, as in `slhdsa-c`.
+#[cfg(slhdsa_c_keccak)]
+#[no_mangle]
+unsafe extern "C" fn slhdsa_rs_keccak_f1600(state: *mut u64) {
+    let state = unsafe { &mut *state.cast::<[u64; 25]>() };
+    keccak::f1600(state);
+}
+
+#[cfg(slhdsa_c_sha2_compress)]
+use sha2::digest::generic_array::GenericArray;
+
+#[cfg(all(test, slhdsa_c_sha2_compress))]
+unsafe extern "C" {
+    /// The C compression functions, renamed by the build script
+    fn slhdsa_c_sha2_256_compress(v: *mut core::ffi::c_void);
+    fn slhdsa_c_sha2_512_compress(v: *mut core::ffi::c_void);
+}
+
+/// The SHA-256 compression function called by the C library, in place of
+/// its own
+///
+/// # Safety
+///
+/// `v` must point to the state (8 big-endian 32-bit words) followed by the
+/// 64-byte block, as in `slhdsa-c`.
+#[cfg(slhdsa_c_sha2_compress)]
+#[no_mangle]
+unsafe extern "C" fn slhdsa_rs_sha2_256_compress(v: *mut core::ffi::c_void) {
+    let v = unsafe { &mut *v.cast::<[u8; 32 + 64]>() };
+    let (state, block) = v.split_at_mut(32);
+    let mut words = [0u32; 8];
+    for (word, bytes) in words.iter_mut().zip(state.chunks_exact(4)) {
+        let mut be = [0u8; 4];
+        be.copy_from_slice(bytes);
+        *word = u32::from_be_bytes(be);
+    }
+    sha2::compress256(&mut words, &[*GenericArray::from_slice(block)]);
+    for (bytes, word) in state.chunks_exact_mut(4).zip(words) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
+/// The SHA-512 compression function called by the C library, in place of
+/// its own
+///
+/// # Safety
+///
+/// `v` must point to the state (8 big-endian 64-bit words) followed by the
+/// 128-byte block, as in `slhdsa-c`.
+#[cfg(slhdsa_c_sha2_compress)]
+#[no_mangle]
+unsafe extern "C" fn slhdsa_rs_sha2_512_compress(v: *mut core::ffi::c_void) {
+    let v = unsafe { &mut *v.cast::<[u8; 64 + 128]>() };
+    let (state, block) = v.split_at_mut(64);
+    let mut words = [0u64; 8];
+    for (word, bytes) in words.iter_mut().zip(state.chunks_exact(8)) {
+        let mut be = [0u8; 8];
+        be.copy_from_slice(bytes);
+        *word = u64::from_be_bytes(be);
+    }
+    sha2::compress512(&mut words, &[*GenericArray::from_slice(block)]);
+    for (bytes, word) in state.chunks_exact_mut(8).zip(words) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
+#[cfg(all(test, any(slhdsa_c_keccak, slhdsa_c_sha2_compress)))]
+mod tests {
+    use super::*;
+
+    /// Arbitrary but deterministic bytes
+    #[cfg(slhdsa_c_sha2_compress)]
+    fn test_bytes<const N: usize>() -> [u8; N] {
+        let mut x = 0u64;
+        core::array::from_fn(|_| {
+            x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            x.to_le_bytes()[7]
+        })
+    }
+
+    /// Apply `f` four times to a copy of `v`, in a buffer aligned as `A`
+    #[cfg(slhdsa_c_sha2_compress)]
+    fn compress_with<A: Copy + Default, const N: usize>(
+        v: [u8; N],
+        f: unsafe extern "C" fn(*mut core::ffi::c_void),
+    ) -> [u8; N] {
+        let mut s = [A::default(); 32];
+        assert!(core::mem::size_of_val(&s) >= N);
+        let bytes = s.as_mut_ptr().cast::<[u8; N]>();
+        unsafe {
+            *bytes = v;
+            for _ in 0..4 {
+                f(bytes.cast());
+            }
+            *bytes
+        }
+    }
+
+    #[cfg(slhdsa_c_keccak)]
+    #[test]
+    fn test_permutation_matches() {
+        let mut state = [0u64; 25];
+        let mut x = 0u64;
+        for lane in &mut state {
+            x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            *lane = x;
+        }
+        let permute = |f: unsafe extern "C" fn(*mut u64)| {
+            let mut s = state;
+            for _ in 0..4 {
+                unsafe { f(s.as_mut_ptr()) };
+            }
+            s
+        };
+        let c = permute(slhdsa_c_keccak_f1600);
+        assert_eq!(c, permute(slhdsa_rs_keccak_f1600));
+        assert_ne!(c, state);
+    }
+
+    #[cfg(slhdsa_c_sha2_compress)]
+    #[test]
+    fn test_compress_matches() {
+        // Aligned as the `uint32_t` array of `sha2_256_t`
+        let v = test_bytes::<{ 32 + 64 }>();
+        let c = compress_with::<u32, _>(v, slhdsa_c_sha2_256_compress);
+        assert_eq!(c, compress_with::<u32, _>(v, slhdsa_rs_sha2_256_compress));
+        assert_ne!(c[..32], v[..32]);
+        assert_eq!(c[32..], v[32..]);
+
+        // Aligned as the `uint64_t` array of `sha2_512_t`
+        let v = test_bytes::<{ 64 + 128 }>();
+        let c = compress_with::<u64, _>(v, slhdsa_c_sha2_512_compress);
+        assert_eq!(c, compress_with::<u64, _>(v, slhdsa_rs_sha2_512_compress));
+        assert_ne!(c[..64], v[..64]);
+        assert_eq!(c[64..], v[64..]);
+    }
+}
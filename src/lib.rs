@@ -19,7 +19,7 @@
 //! While the API exposed by SLH-DSA is the same as conventional
 //! signature schemes, it is important to note that the signatures
 //! produced by the algorithm are much larger than classical schemes
-//! like EdDSA, ranging from over 7KB for the smallest parameter set to
+//! like `EdDSA`, ranging from over 7KB for the smallest parameter set to
 //! nearly 50KB at the largest
 //!
 //! Raw FFI bindings are available in the `ffi` module of this crate,
@@ -28,12 +28,29 @@
 //!
 //! [RustCrypto]: https://github.com/RustCrypto
 //!
-#![cfg_attr(feature = "wrapper", doc = include_str!("../docs/wrapper.md"))]
-#![cfg_attr(not(feature = "wrapper"), doc = include_str!("../docs/ffi_only.md"))]
+#![cfg_attr(feature = "sign", doc = include_str!("../docs/wrapper.md"))]
+#![cfg_attr(
+    all(feature = "verify", not(feature = "sign")),
+    doc = include_str!("../docs/verify_only.md")
+)]
+#![cfg_attr(not(feature = "verify"), doc = include_str!("../docs/ffi_only.md"))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(
+    feature = "async",
+    feature = "mmap",
+    feature = "rayon",
+    feature = "stream"
+))]
+extern crate std;
+
+pub mod build_info;
 pub mod ffi;
+#[cfg(any(feature = "rust-keccak", feature = "rust-sha2"))]
+mod hash_backend;
 
-#[cfg(feature = "wrapper")]
+#[cfg(feature = "verify")]
 mod wrapper;
-#[cfg(feature = "wrapper")]
+#[cfg(feature = "verify")]
 pub use wrapper::*;
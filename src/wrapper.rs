@@ -1,17 +1,72 @@
 #![allow(clippy::must_use_candidate)]
+// Without any parameter set (which `build.rs` warns about), most of the
+// wrapper is unused
+#![cfg_attr(
+    not(any(
+        feature = "sha2-128s",
+        feature = "sha2-128f",
+        feature = "sha2-192s",
+        feature = "sha2-192f",
+        feature = "sha2-256s",
+        feature = "sha2-256f",
+        feature = "shake-128s",
+        feature = "shake-128f",
+        feature = "shake-192s",
+        feature = "shake-192f",
+        feature = "shake-256s",
+        feature = "shake-256f"
+    )),
+    allow(dead_code, unused_imports, unused_macros, unused_variables)
+)]
 
 pub use signature;
+#[cfg(feature = "slh-dsa")]
+pub use slh_dsa;
 
 use crate::ffi;
 
+#[cfg(feature = "acvp")]
+pub mod acvp;
+#[cfg(feature = "alloc")]
+pub mod batch;
+#[cfg(all(feature = "custom", not(feature = "system")))]
+pub mod custom;
+#[cfg(feature = "sign")]
+pub mod entropy;
+#[cfg(feature = "kat")]
+pub mod kat;
+#[cfg(feature = "merkle")]
+pub mod merkle;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+#[cfg(any(feature = "alloc", all(feature = "stream", not(feature = "system"))))]
+mod multipart;
+#[cfg(feature = "async")]
+pub mod nonblocking;
 pub mod parameter_sets;
+#[cfg(all(feature = "prepared", not(feature = "system")))]
+mod prepared;
+#[cfg(all(test, feature = "sign"))]
+mod proptests;
 mod signature_encoding;
+#[cfg(feature = "sign")]
 mod signing_key;
+mod slh_dsa_compat;
+#[cfg(all(feature = "stream", not(feature = "system")))]
+pub mod stream;
 mod verifying_key;
 
+#[cfg(any(feature = "alloc", all(feature = "stream", not(feature = "system"))))]
+pub use multipart::{MultipartSigner, MultipartVerifier};
 pub use parameter_sets::all::*;
+#[cfg(all(feature = "prepared", feature = "sign", not(feature = "system")))]
+pub use prepared::PreparedSigningKey;
+#[cfg(all(feature = "prepared", not(feature = "system")))]
+pub use prepared::PreparedVerifyingKey;
 pub use signature_encoding::*;
+#[cfg(feature = "sign")]
 pub use signing_key::keygen;
+#[cfg(feature = "sign")]
 pub use signing_key::*;
 pub use verifying_key::*;
 
@@ -20,7 +75,24 @@ pub use transcoding::*;
 pub use utils::transcoding;
 pub(crate) use utils::typenum;
 
+/// The empty context string, used by the context-less `Signer` and
+/// `Verifier` trait implementations.
+pub(crate) const EMPTY_CTX: &[u8; 0] = &[];
+
+/// Hash family of a parameter set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashFamily {
+    /// SHA2-based parameter sets
+    Sha2,
+    /// SHAKE-based parameter sets
+    Shake,
+}
+
 trait FFIParams {
+    /// Hash family, which also selects the `PRF_msg` and `H_msg` functions
+    #[cfg_attr(not(feature = "stream"), allow(dead_code))]
+    const HASH_FAMILY: HashFamily;
+
     /// Returns a static reference to the FFI struct representing this parameter set.
     ///
     /// Calling this function does not have runtime overhead: since the FFI
@@ -38,6 +110,42 @@ trait FFIParams {
     }
 }
 
+/// Check, once per parameter set, that a prebuilt library uses the sizes
+/// of `P`, as the C code writes keys and signatures into buffers of these
+/// sizes.
+///
+/// Unlike with the bundled sources, these sizes are not read from the C
+/// parameter tables at build time, but are the ones of FIPS 205.
+///
+/// # Panics
+///
+/// Panics if the sizes differ.
+#[cfg(feature = "system")]
+fn check_system_sizes<P: SigningKeyLen + VerifyingKeyLen + SignatureLen + ParameterSet>(
+    prm: &ffi::slh_param_s,
+    checked: &core::sync::atomic::AtomicBool,
+) {
+    use core::sync::atomic::Ordering;
+
+    if checked.load(Ordering::Relaxed) {
+        return;
+    }
+    let sizes = unsafe {
+        (
+            ffi::slh_pk_sz(prm),
+            ffi::slh_sk_sz(prm),
+            ffi::slh_sig_sz(prm),
+        )
+    };
+    assert_eq!(
+        sizes,
+        (P::VERIFYING_KEY_LEN, P::SIGNING_KEY_LEN, P::SIGNATURE_LEN),
+        "The system slhdsa library does not use the sizes of {}",
+        P::NAME
+    );
+    checked.store(true, Ordering::Relaxed);
+}
+
 /// Trait for types that provide the length of the secret (signing) key.
 #[allow(private_bounds)]
 pub trait SigningKeyLen: FFIParams {
@@ -155,6 +263,7 @@ pub trait ParameterSet:
     /// assert_eq!(private_key.as_bytes().len(), P::SIGNING_KEY_LEN);
     /// assert_eq!(public_key.as_bytes().len(), P::VERIFYING_KEY_LEN);
     /// ```
+    #[cfg(feature = "sign")]
     fn keygen() -> Result<(SigningKey<Self>, VerifyingKey<Self>), KeygenError> {
         signing_key::keygen::<Self>()
     }
@@ -195,6 +304,7 @@ mod tests {
     }
     test_parameter_sets!(test_sizes);
 
+    #[cfg(feature = "sign")]
     fn test_sign_verify<P: ParameterSet>() {
         //let mut rng = rand::rng();
         //let sk = SigningKey::<P>::new(&mut rng);
@@ -220,91 +330,29 @@ mod tests {
         vk.verify(msg, &sig)
             .expect("verify() should not fail on the happy path");
     }
+    #[cfg(feature = "sign")]
     test_parameter_sets!(test_sign_verify);
 
-    //     // Check signature fails on modified message
-    //     #[test]
-    //     fn test_sign_verify_shake_128f_fail_on_modified_message() {
-    //         let mut rng = rand::rng();
-    //         let sk = SigningKey::<Shake128f>::new(&mut rng);
-    //         let msg = b"Hello, world!";
-    //         let modified_msg = b"Goodbye, world!";
-    //
-    //         let sig = sk.try_sign(msg).unwrap();
-    //         let vk = sk.verifying_key();
-    //         assert!(vk.verify(msg, &sig).is_ok());
-    //         assert!(vk.verify(modified_msg, &sig).is_err());
-    //     }
-    //
-    //     #[test]
-    //     fn test_sign_verify_fail_with_wrong_verifying_key() {
-    //         let mut rng = rand::rng();
-    //         let sk = SigningKey::<Shake128f>::new(&mut rng);
-    //         let wrong_sk = SigningKey::<Shake128f>::new(&mut rng); // Generate a different signing key
-    //         let msg = b"Hello, world!";
-    //
-    //         let sig = sk.try_sign(msg).unwrap();
-    //         let vk = sk.verifying_key();
-    //         let wrong_vk = wrong_sk.verifying_key(); // Get the verifying key of the wrong signing key
-    //         assert!(vk.verify(msg, &sig).is_ok());
-    //         assert!(wrong_vk.verify(msg, &sig).is_err()); // This should fail because the verifying key does not match the signing key used
-    //     }
-    //
-    //     #[test]
-    //     fn test_sign_verify_fail_on_modified_signature() {
-    //         let mut rng = rand::rng();
-    //         let sk = SigningKey::<Shake128f>::new(&mut rng);
-    //         let msg = b"Hello, world!";
-    //
-    //         let mut sig_bytes = sk.try_sign(msg).unwrap().to_bytes();
-    //         // Randomly modify one byte in the signature
-    //         let sig_len = sig_bytes.len();
-    //         let random_byte_index = rng.random_range(0..sig_len);
-    //         sig_bytes[random_byte_index] ^= 0xff; // Invert one byte to ensure it's different
-    //         let sig = (&sig_bytes).into();
-    //
-    //         let vk = sk.verifying_key();
-    //         assert!(
-    //             vk.verify(msg, &sig).is_err(),
-    //             "Verification should fail with a modified signature"
-    //         );
-    //     }
-    //
-    //     #[test]
-    //     fn test_successive_signatures_not_equal() {
-    //         let mut rng = rand::rng();
-    //         let sk = SigningKey::<Shake128f>::new(&mut rng);
-    //         let msg = b"Hello, world!";
-    //
-    //         let sig1 = sk.try_sign_with_rng(&mut rng, msg).unwrap();
-    //         let sig2 = sk.try_sign_with_rng(&mut rng, msg).unwrap();
-    //
-    //         assert_ne!(
-    //             sig1, sig2,
-    //             "Two successive randomized signatures over the same message should not be equal"
-    //         );
-    //     }
-    //
-    //     #[test]
-    //     fn test_sign_verify_nonempty_context() {
-    //         let mut rng = rand::rng();
-    //         let sk = SigningKey::<Shake128f>::new(&mut rng);
-    //         let vk = sk.verifying_key();
-    //         let msg = b"Hello, world!";
-    //         let ctx = b"Test context";
-    //         let sig = sk.try_sign_with_context(msg, ctx, None).unwrap();
-    //         vk.try_verify_with_context(msg, ctx, &sig).unwrap();
-    //     }
-    //
-    //     #[test]
-    //     fn test_sign_verify_wrong_context() {
-    //         let mut rng = rand::rng();
-    //         let sk = SigningKey::<Shake128f>::new(&mut rng);
-    //         let vk = sk.verifying_key();
-    //         let msg = b"Hello, world!";
-    //         let ctx = b"Test context!";
-    //         let wrong_ctx = b"Wrong context";
-    //         let sig = sk.try_sign_with_context(msg, ctx, None).unwrap();
-    //         assert!(vk.try_verify_with_context(msg, wrong_ctx, &sig).is_err());
-    //     }
+    #[cfg(feature = "sign")]
+    fn test_sign_verify_hedged<P: ParameterSet>() {
+        let sk = SigningKey::<P>::new().expect("Keygen failed");
+        let vk = sk.verifying_key();
+        let msg = b"Hello, world!";
+        let ctx = b"Test context";
+
+        let sig1 = sk
+            .try_sign_hedged_with_ctx(msg, ctx)
+            .expect("Hedged signing failed");
+        let sig2 = sk
+            .try_sign_with_rng(&mut rand::rngs::SysRng, msg)
+            .expect("Hedged signing failed");
+
+        vk.verify_with_ctx(msg, ctx, &sig1)
+            .expect("verify_with_ctx() should not fail on the happy path");
+        vk.verify(msg, &sig2)
+            .expect("verify() should not fail on the happy path");
+        assert_ne!(sig1, sk.try_sign_with_ctx(msg, ctx).unwrap());
+    }
+    #[cfg(feature = "sign")]
+    test_parameter_sets!(test_sign_verify_hedged);
 }
@@ -0,0 +1,537 @@
+//! Runner for the NIST ACVP SLH-DSA JSON test vectors.
+//!
+//! NIST publishes the SLH-DSA test vectors for the Automated Cryptographic
+//! Validation Protocol (ACVP) as JSON files, one per mode:
+//!
+//! - `keyGen`: keypairs derived from the given `skSeed`, `skPrf` and
+//!   `pkSeed` (Algorithm 18 of FIPS 205);
+//! - `sigGen`: signatures over the internal (`M'`) or external
+//!   (pure/pre-hash, with context) interfaces, either deterministic or with
+//!   `additionalRandomness`;
+//! - `sigVer`: signatures that are expected to verify (`testPassed`) or not.
+//!
+//! [`run_json()`] runs every test case of such a file (in the
+//! `internalProjection.json` format of the
+//! [ACVP-Server](https://github.com/usnistgov/ACVP-Server) repository,
+//! which embeds the expected results) through this crate, and collects the
+//! outcome of each test case in a [`Report`]. [`run_prompt()`] does the
+//! same for a `prompt.json` file and its `expectedResults.json` companion.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use slhdsa_c_rs::acvp;
+//!
+//! let json = r#"{
+//!     "vsId": 0,
+//!     "algorithm": "SLH-DSA",
+//!     "mode": "sigVer",
+//!     "revision": "FIPS205",
+//!     "testGroups": []
+//! }"#;
+//!
+//! let report = acvp::run_json(json).expect("Invalid vector set");
+//! assert_eq!(report.failed(), 0);
+//! println!("{report}");
+//! ```
+
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::Deserialize;
+
+#[allow(clippy::wildcard_imports)]
+use super::parameter_sets::*;
+use super::transcoding::AsBytes;
+use super::{keygen_from_seeds, ParameterSet, Signature, SigningKey, VerifyingKey};
+
+/// The ACVP modes supported by the runner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// `keyGen` test vectors
+    KeyGen,
+    /// `sigGen` test vectors
+    SigGen,
+    /// `sigVer` test vectors
+    SigVer,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::KeyGen => write!(f, "keyGen"),
+            Mode::SigGen => write!(f, "sigGen"),
+            Mode::SigVer => write!(f, "sigVer"),
+        }
+    }
+}
+
+/// Outcome of a single test case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The crate produced the expected result.
+    Passed,
+    /// The crate did not produce the expected result, for the given reason.
+    Failed(String),
+    /// The test case was not run, for the given reason.
+    Skipped(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "passed"),
+            Outcome::Failed(reason) => write!(f, "FAILED ({reason})"),
+            Outcome::Skipped(reason) => write!(f, "skipped ({reason})"),
+        }
+    }
+}
+
+/// Result of a single test case, identified by its `tgId` and `tcId`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCaseResult {
+    /// Test group identifier
+    pub tg_id: u64,
+    /// Test case identifier
+    pub tc_id: u64,
+    /// Parameter set of the test group, e.g. `SLH-DSA-SHA2-128s`
+    pub parameter_set: String,
+    /// Outcome of the test case
+    pub outcome: Outcome,
+}
+
+/// Per-test-case results of a vector set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Mode of the vector set
+    pub mode: Mode,
+    /// Results of each test case, in the order they appear in the file
+    pub results: Vec<TestCaseResult>,
+}
+
+impl Report {
+    fn count(&self, f: impl Fn(&Outcome) -> bool) -> usize {
+        self.results.iter().filter(|r| f(&r.outcome)).count()
+    }
+
+    /// Number of test cases that passed
+    pub fn passed(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Passed))
+    }
+
+    /// Number of test cases that failed
+    pub fn failed(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Failed(_)))
+    }
+
+    /// Number of test cases that were skipped
+    pub fn skipped(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Skipped(_)))
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in &self.results {
+            writeln!(
+                f,
+                "{} tgId={} tcId={} {}: {}",
+                self.mode, r.tg_id, r.tc_id, r.parameter_set, r.outcome
+            )?;
+        }
+        write!(
+            f,
+            "{}: {} passed, {} failed, {} skipped",
+            self.mode,
+            self.passed(),
+            self.failed(),
+            self.skipped()
+        )
+    }
+}
+
+/// Errors that prevent a vector set from being run at all.
+#[derive(Debug)]
+pub enum AcvpError {
+    /// The input is not a valid ACVP JSON vector set.
+    Json(serde_json::Error),
+    /// The vector set is not for SLH-DSA.
+    UnsupportedAlgorithm(String),
+    /// The vector set mode is not supported.
+    UnsupportedMode(String),
+}
+
+impl fmt::Display for AcvpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AcvpError::Json(e) => write!(f, "Invalid ACVP JSON: {e}"),
+            AcvpError::UnsupportedAlgorithm(a) => write!(f, "Unsupported algorithm {a:?}"),
+            AcvpError::UnsupportedMode(m) => write!(f, "Unsupported mode {m:?}"),
+        }
+    }
+}
+
+impl core::error::Error for AcvpError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            AcvpError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VectorSet {
+    algorithm: String,
+    mode: String,
+    test_groups: Vec<TestGroup>,
+}
+
+/// `expectedResults.json`: the expected values of each test case, by
+/// `tgId` and `tcId`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExpectedResults {
+    test_groups: Vec<ExpectedGroup>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExpectedGroup {
+    tg_id: u64,
+    tests: Vec<TestCase>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestGroup {
+    tg_id: u64,
+    parameter_set: String,
+    signature_interface: Option<String>,
+    pre_hash: Option<String>,
+    pk: Option<String>,
+    tests: Vec<TestCase>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestCase {
+    tc_id: u64,
+    sk_seed: Option<String>,
+    sk_prf: Option<String>,
+    pk_seed: Option<String>,
+    sk: Option<String>,
+    pk: Option<String>,
+    message: Option<String>,
+    context: Option<String>,
+    hash_alg: Option<String>,
+    additional_randomness: Option<String>,
+    signature: Option<String>,
+    test_passed: Option<bool>,
+}
+
+impl TestCase {
+    /// Fill the fields missing from the prompt with the expected results
+    fn merge(&mut self, expected: TestCase) {
+        macro_rules! merge {
+            ($($field:ident),+) => {
+                $(
+                    if self.$field.is_none() {
+                        self.$field = expected.$field;
+                    }
+                )+
+            };
+        }
+        merge!(sk, pk, signature, test_passed);
+    }
+}
+
+/// Run all the test cases of an ACVP SLH-DSA vector set.
+///
+/// # Errors
+///
+/// Returns an [`AcvpError`] if `json` cannot be parsed, or does not contain
+/// SLH-DSA `keyGen`, `sigGen` or `sigVer` test vectors. Failures of
+/// individual test cases are reported in the returned [`Report`] instead.
+pub fn run_json(json: &str) -> Result<Report, AcvpError> {
+    let vs: VectorSet = serde_json::from_str(json).map_err(AcvpError::Json)?;
+    run_vector_set(&vs)
+}
+
+/// Run all the test cases of an ACVP SLH-DSA vector set, given as a
+/// `prompt.json` file and the matching `expectedResults.json`.
+///
+/// Test cases without expected results fail.
+///
+/// # Errors
+///
+/// Returns an [`AcvpError`] if either file cannot be parsed, or if `prompt`
+/// does not contain SLH-DSA `keyGen`, `sigGen` or `sigVer` test vectors.
+pub fn run_prompt(prompt: &str, expected_results: &str) -> Result<Report, AcvpError> {
+    let mut vs: VectorSet = serde_json::from_str(prompt).map_err(AcvpError::Json)?;
+    let expected: ExpectedResults =
+        serde_json::from_str(expected_results).map_err(AcvpError::Json)?;
+
+    for expected_group in expected.test_groups {
+        let Some(group) = vs
+            .test_groups
+            .iter_mut()
+            .find(|group| group.tg_id == expected_group.tg_id)
+        else {
+            continue;
+        };
+        for expected_tc in expected_group.tests {
+            if let Some(tc) = group
+                .tests
+                .iter_mut()
+                .find(|tc| tc.tc_id == expected_tc.tc_id)
+            {
+                tc.merge(expected_tc);
+            }
+        }
+    }
+
+    run_vector_set(&vs)
+}
+
+fn run_vector_set(vs: &VectorSet) -> Result<Report, AcvpError> {
+    if vs.algorithm != "SLH-DSA" {
+        return Err(AcvpError::UnsupportedAlgorithm(vs.algorithm.clone()));
+    }
+    let mode = match vs.mode.as_str() {
+        "keyGen" => Mode::KeyGen,
+        "sigGen" => Mode::SigGen,
+        "sigVer" => Mode::SigVer,
+        _ => return Err(AcvpError::UnsupportedMode(vs.mode.clone())),
+    };
+
+    let mut results = Vec::new();
+    for group in &vs.test_groups {
+        let outcomes = dispatch(&group.parameter_set, mode, group).unwrap_or_else(|| {
+            let reason = format!("unknown parameter set {:?}", group.parameter_set);
+            group
+                .tests
+                .iter()
+                .map(|_| Outcome::Skipped(reason.clone()))
+                .collect()
+        });
+
+        results.extend(
+            group
+                .tests
+                .iter()
+                .zip(outcomes)
+                .map(|(tc, outcome)| TestCaseResult {
+                    tg_id: group.tg_id,
+                    tc_id: tc.tc_id,
+                    parameter_set: group.parameter_set.clone(),
+                    outcome,
+                }),
+        );
+    }
+
+    Ok(Report { mode, results })
+}
+
+/// Run a test group with the parameter set named `name`, if it exists.
+fn dispatch(name: &str, mode: Mode, group: &TestGroup) -> Option<Vec<Outcome>> {
+    macro_rules! dispatch_to {
+        ($($(#[$meta:meta])* $P:ident),+ $(,)?) => {
+            $(
+                $(#[$meta])*
+                if name == $P::NAME {
+                    return Some(run_group::<$P>(mode, group));
+                }
+            )+
+        };
+    }
+
+    dispatch_to!(
+        #[cfg(feature = "sha2-128s")]
+        SLH_DSA_SHA2_128s,
+        #[cfg(feature = "sha2-128f")]
+        SLH_DSA_SHA2_128f,
+        #[cfg(feature = "sha2-192s")]
+        SLH_DSA_SHA2_192s,
+        #[cfg(feature = "sha2-192f")]
+        SLH_DSA_SHA2_192f,
+        #[cfg(feature = "sha2-256s")]
+        SLH_DSA_SHA2_256s,
+        #[cfg(feature = "sha2-256f")]
+        SLH_DSA_SHA2_256f,
+        #[cfg(feature = "shake-128s")]
+        SLH_DSA_SHAKE_128s,
+        #[cfg(feature = "shake-128f")]
+        SLH_DSA_SHAKE_128f,
+        #[cfg(feature = "shake-192s")]
+        SLH_DSA_SHAKE_192s,
+        #[cfg(feature = "shake-192f")]
+        SLH_DSA_SHAKE_192f,
+        #[cfg(feature = "shake-256s")]
+        SLH_DSA_SHAKE_256s,
+        #[cfg(feature = "shake-256f")]
+        SLH_DSA_SHAKE_256f,
+    );
+    None
+}
+
+type CaseResult = Result<(), String>;
+
+fn run_group<P: ParameterSet>(mode: Mode, group: &TestGroup) -> Vec<Outcome> {
+    group
+        .tests
+        .iter()
+        .map(|tc| {
+            let ret = match mode {
+                Mode::KeyGen => keygen_case::<P>(tc),
+                Mode::SigGen => siggen_case::<P>(group, tc),
+                Mode::SigVer => sigver_case::<P>(group, tc),
+            };
+            match ret {
+                Ok(()) => Outcome::Passed,
+                Err(reason) => Outcome::Failed(reason),
+            }
+        })
+        .collect()
+}
+
+fn keygen_case<P: ParameterSet>(tc: &TestCase) -> CaseResult {
+    let sk_seed = unhex("skSeed", tc.sk_seed.as_ref())?;
+    let sk_prf = unhex("skPrf", tc.sk_prf.as_ref())?;
+    let pk_seed = unhex("pkSeed", tc.pk_seed.as_ref())?;
+
+    let (sk, pk) = keygen_from_seeds::<P>(&sk_seed, &sk_prf, &pk_seed)
+        .map_err(|e| format!("keygen failed: {e}"))?;
+
+    expect_eq("sk", sk.as_bytes(), &unhex("sk", tc.sk.as_ref())?)?;
+    expect_eq("pk", pk.as_bytes(), &unhex("pk", tc.pk.as_ref())?)
+}
+
+fn siggen_case<P: ParameterSet>(group: &TestGroup, tc: &TestCase) -> CaseResult {
+    let sk = unhex("sk", tc.sk.as_ref())?;
+    let sk = SigningKey::<P>::try_from(sk.as_slice()).map_err(|e| format!("invalid sk: {e}"))?;
+    let message = unhex("message", tc.message.as_ref())?;
+    let addrnd = match &tc.additional_randomness {
+        Some(addrnd) => Some(unhex("additionalRandomness", Some(addrnd))?),
+        None => None,
+    };
+    let addrnd = addrnd.as_deref();
+
+    let sig = match Interface::of(group)? {
+        Interface::Internal => sk.sign_internal(&message, addrnd),
+        Interface::Pure => {
+            let context = unhex_or_empty("context", tc.context.as_ref())?;
+            sk.sign_inner(&message, &context, addrnd)
+        }
+        Interface::PreHash => {
+            let context = unhex_or_empty("context", tc.context.as_ref())?;
+            let ph = hash_alg(tc)?;
+            sk.sign_prehash(&message, &context, &ph, addrnd)
+        }
+    }
+    .map_err(|e| format!("signing failed: {e}"))?;
+
+    expect_eq(
+        "signature",
+        sig.as_bytes(),
+        &unhex("signature", tc.signature.as_ref())?,
+    )
+}
+
+fn sigver_case<P: ParameterSet>(group: &TestGroup, tc: &TestCase) -> CaseResult {
+    let expected = tc
+        .test_passed
+        .ok_or_else(|| "missing field `testPassed`".to_string())?;
+    let pk = unhex("pk", tc.pk.as_ref().or(group.pk.as_ref()))?;
+    let vk = VerifyingKey::<P>::try_from(pk.as_slice()).map_err(|e| format!("invalid pk: {e}"))?;
+    let message = unhex("message", tc.message.as_ref())?;
+    let signature = unhex("signature", tc.signature.as_ref())?;
+
+    let ret = match Interface::of(group)? {
+        Interface::Internal => vk.verify_internal(&message, &signature),
+        Interface::Pure => {
+            let context = unhex_or_empty("context", tc.context.as_ref())?;
+            Signature::<P>::try_from(signature.as_slice())
+                .and_then(|sig| vk.verify_with_ctx(&message, &context, &sig))
+        }
+        Interface::PreHash => {
+            let context = unhex_or_empty("context", tc.context.as_ref())?;
+            let ph = hash_alg(tc)?;
+            vk.verify_prehash(&message, &context, &ph, &signature)
+        }
+    };
+
+    let actual = ret.is_ok();
+    if actual != expected {
+        return Err(format!(
+            "verification returned {actual}, expected testPassed={expected}"
+        ));
+    }
+    Ok(())
+}
+
+/// The FIPS 205 signing/verification interface selected by a test group.
+enum Interface {
+    /// `slh_sign_internal()`/`slh_verify_internal()`
+    Internal,
+    /// `slh_sign()`/`slh_verify()`
+    Pure,
+    /// `hash_slh_sign()`/`hash_slh_verify()`
+    PreHash,
+}
+
+impl Interface {
+    fn of(group: &TestGroup) -> Result<Self, String> {
+        match (
+            group.signature_interface.as_deref(),
+            group.pre_hash.as_deref(),
+        ) {
+            (Some("internal"), _) => Ok(Interface::Internal),
+            (None | Some("external"), None | Some("pure")) => Ok(Interface::Pure),
+            (None | Some("external"), Some("preHash")) => Ok(Interface::PreHash),
+            (interface, pre_hash) => Err(format!(
+                "unsupported signatureInterface={interface:?} preHash={pre_hash:?}"
+            )),
+        }
+    }
+}
+
+fn hash_alg(tc: &TestCase) -> Result<CString, String> {
+    let alg = tc
+        .hash_alg
+        .as_ref()
+        .ok_or_else(|| "missing field `hashAlg`".to_string())?;
+    CString::new(alg.as_str()).map_err(|_| format!("invalid hashAlg {alg:?}"))
+}
+
+fn expect_eq(field: &str, actual: &[u8], expected: &[u8]) -> CaseResult {
+    if actual != expected {
+        return Err(format!("`{field}` mismatch"));
+    }
+    Ok(())
+}
+
+fn unhex_or_empty(field: &str, value: Option<&String>) -> Result<Vec<u8>, String> {
+    match value {
+        Some(value) => unhex(field, Some(value)),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn unhex(field: &str, value: Option<&String>) -> Result<Vec<u8>, String> {
+    let value = value.ok_or_else(|| format!("missing field `{field}`"))?;
+    if !value.is_ascii() || value.len() % 2 != 0 {
+        return Err(format!("invalid hex in `{field}`"));
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&value[i..i + 2], 16)
+                .map_err(|_| format!("invalid hex in `{field}`"))
+        })
+        .collect()
+}
@@ -0,0 +1,669 @@
+//! Verifying many signatures at once.
+//!
+//! [`verify_batch()`] checks every entry of a batch, and reports which ones
+//! failed. With the `rayon` feature, the entries are spread across the
+//! threads of the global [`rayon`] pool; otherwise they are checked
+//! sequentially. SLH-DSA has no batch verification shortcut: this is only
+//! about using all the available cores.
+//!
+//! Each entry is a [`BatchItem`], e.g. a `(VerifyingKey<P>, message,
+//! context, Signature<P>)` tuple or a [`BatchEntry`] borrowing them:
+//!
+//! ```rust
+//! use slhdsa_c_rs::batch::{verify_batch, BatchEntry};
+//! use slhdsa_c_rs::*;
+//! use SLH_DSA_SHAKE_128f as P;
+//!
+//! let (sk, vk) = keygen::<P>().expect("Keygen failed");
+//! let messages: [&[u8]; 3] = [b"first", b"second", b"third"];
+//! let signatures: Vec<_> = messages.iter().map(|m| sk.sign(m)).collect();
+//!
+//! let mut batch: Vec<_> = messages
+//!     .iter()
+//!     .zip(&signatures)
+//!     .map(|(m, sig)| BatchEntry::new(&vk, m, b"", sig))
+//!     .collect();
+//! assert!(verify_batch(&batch).is_ok());
+//!
+//! // Swap two signatures
+//! batch[0].signature = &signatures[2];
+//! batch[2].signature = &signatures[0];
+//! let err = verify_batch(&batch).unwrap_err();
+//! assert_eq!(err.failed(), &[0, 2]);
+//! ```
+//!
+//! Batches mixing parameter sets are made of trait objects, e.g.
+//! `Vec<Box<dyn BatchItem + Send + Sync>>`.
+//!
+//! Likewise, [`SigningKey::sign_batch()`] signs many messages, on a
+//! [`rayon::ThreadPool`] with the `rayon` feature. Signatures are handed
+//! over in order, as soon as the previous ones are, and only a bounded
+//! number of them is kept in memory at once:
+//!
+//! ```rust
+//! use slhdsa_c_rs::batch::SignBatchOptions;
+//! use slhdsa_c_rs::*;
+//! use SLH_DSA_SHAKE_128f as P;
+//!
+//! let (sk, vk) = keygen::<P>().expect("Keygen failed");
+//! let messages: Vec<Vec<u8>> = (0u8..4).map(|i| vec![i; 64]).collect();
+//!
+//! let options = SignBatchOptions::new().context(b"release").max_in_flight(2);
+//! sk.sign_batch(&messages, &options, |i, sig| {
+//!     assert!(vk.verify_with_ctx(&messages[i], b"release", &sig).is_ok());
+//! })
+//! .expect("Signing failed");
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "sign")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "sign")]
+use super::SigningKey;
+use super::{ParameterSet, Signature, VerifyingKey};
+
+/// An entry of a batch passed to [`verify_batch()`]
+pub trait BatchItem {
+    /// Verify this entry on its own
+    ///
+    /// # Errors
+    ///
+    /// Returns [`signature::Error`] if the signature is inauthentic.
+    fn verify_item(&self) -> Result<(), signature::Error>;
+}
+
+/// A signature to verify, with its verifying key, message and context
+#[derive(Debug)]
+pub struct BatchEntry<'a, P: ParameterSet> {
+    /// The verifying key
+    pub verifying_key: &'a VerifyingKey<P>,
+    /// The signed message
+    pub message: &'a [u8],
+    /// The context string the message was signed under
+    pub context: &'a [u8],
+    /// The signature
+    pub signature: &'a Signature<P>,
+}
+
+// Manual impls: deriving would require `P: Clone + Copy`
+#[allow(clippy::expl_impl_clone_on_copy)]
+impl<P: ParameterSet> Clone for BatchEntry<'_, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: ParameterSet> Copy for BatchEntry<'_, P> {}
+
+impl<'a, P: ParameterSet> BatchEntry<'a, P> {
+    /// A new entry, signed under `context`
+    pub fn new(
+        verifying_key: &'a VerifyingKey<P>,
+        message: &'a [u8],
+        context: &'a [u8],
+        signature: &'a Signature<P>,
+    ) -> Self {
+        Self {
+            verifying_key,
+            message,
+            context,
+            signature,
+        }
+    }
+}
+
+impl<P: ParameterSet> BatchItem for BatchEntry<'_, P> {
+    fn verify_item(&self) -> Result<(), signature::Error> {
+        self.verifying_key
+            .verify_with_ctx(self.message, self.context, self.signature)
+    }
+}
+
+impl<P, M, C> BatchItem for (VerifyingKey<P>, M, C, Signature<P>)
+where
+    P: ParameterSet,
+    M: AsRef<[u8]>,
+    C: AsRef<[u8]>,
+{
+    fn verify_item(&self) -> Result<(), signature::Error> {
+        let (vk, message, context, signature) = self;
+        vk.verify_with_ctx(message.as_ref(), context.as_ref(), signature)
+    }
+}
+
+impl<T: BatchItem + ?Sized> BatchItem for &T {
+    fn verify_item(&self) -> Result<(), signature::Error> {
+        (**self).verify_item()
+    }
+}
+
+impl<T: BatchItem + ?Sized> BatchItem for alloc::boxed::Box<T> {
+    fn verify_item(&self) -> Result<(), signature::Error> {
+        (**self).verify_item()
+    }
+}
+
+/// Error returned by [`verify_batch()`] when some entries fail to verify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchError {
+    failed: Vec<usize>,
+}
+
+impl BatchError {
+    /// Indices of the entries that failed to verify, in increasing order
+    pub fn failed(&self) -> &[usize] {
+        &self.failed
+    }
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} signature(s) of the batch failed to verify",
+            self.failed.len()
+        )
+    }
+}
+
+impl core::error::Error for BatchError {}
+
+/// Verify every entry of `batch`.
+///
+/// All the entries are checked, even after a failure: the error lists all
+/// the failed ones.
+///
+/// # Errors
+///
+/// Returns a [`BatchError`] with the indices of the entries that failed to
+/// verify, if any.
+pub fn verify_batch<T: BatchItem + Sync>(batch: &[T]) -> Result<(), BatchError> {
+    #[cfg(feature = "rayon")]
+    let failed: Vec<usize> = {
+        use rayon::prelude::*;
+
+        batch
+            .par_iter()
+            .enumerate()
+            .filter(|(_, item)| item.verify_item().is_err())
+            .map(|(i, _)| i)
+            .collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let failed: Vec<usize> = batch
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.verify_item().is_err())
+        .map(|(i, _)| i)
+        .collect();
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(BatchError { failed })
+    }
+}
+
+/// Options of [`SigningKey::sign_batch()`]
+#[cfg(feature = "sign")]
+#[derive(Debug, Clone, Default)]
+pub struct SignBatchOptions<'a> {
+    context: &'a [u8],
+    hedged: bool,
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    max_in_flight: Option<usize>,
+    cancel: Option<&'a AtomicBool>,
+    #[cfg(feature = "rayon")]
+    pool: Option<&'a rayon::ThreadPool>,
+}
+
+#[cfg(feature = "sign")]
+impl<'a> SignBatchOptions<'a> {
+    /// Deterministic signatures under the empty context, on the global
+    /// thread pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sign every message under `context`
+    #[must_use]
+    pub fn context(mut self, context: &'a [u8]) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Produce hedged signatures (see
+    /// [`SigningKey::try_sign_hedged_with_ctx()`]) instead of deterministic
+    /// ones
+    #[must_use]
+    pub fn hedged(mut self, hedged: bool) -> Self {
+        self.hedged = hedged;
+        self
+    }
+
+    /// Maximum number of signatures being produced or waiting to be handed
+    /// over at once (at least 1).
+    ///
+    /// This bounds the memory used by the batch to `max_in_flight`
+    /// signatures. It defaults to twice the number of threads of the pool.
+    /// Without the `rayon` feature, the messages are signed one at a time,
+    /// and this has no effect.
+    #[must_use]
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight.max(1));
+        self
+    }
+
+    /// Stop signing once `cancel` is set.
+    ///
+    /// The flag is checked before signing each message: the signatures
+    /// already being produced are completed, but not handed over.
+    #[must_use]
+    pub fn cancel_on(mut self, cancel: &'a AtomicBool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Sign on `pool` instead of the global thread pool
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn pool(mut self, pool: &'a rayon::ThreadPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.is_some_and(|c| c.load(Ordering::Relaxed))
+    }
+
+    #[cfg(feature = "rayon")]
+    fn window(&self) -> usize {
+        self.max_in_flight.unwrap_or_else(|| {
+            let threads = match self.pool {
+                Some(pool) => pool.current_num_threads(),
+                None => rayon::current_num_threads(),
+            };
+            2 * threads
+        })
+    }
+}
+
+/// Outcome of signing one message of a batch
+#[cfg(feature = "sign")]
+type ItemResult<P> = Result<Signature<P>, signature::Error>;
+
+/// Error returned by [`SigningKey::sign_batch()`]
+#[cfg(feature = "sign")]
+#[derive(Debug)]
+pub enum SignBatchError {
+    /// Signing the message at `index` failed: the signatures of the
+    /// previous messages were handed over
+    Signing {
+        /// Index of the message
+        index: usize,
+        /// The signing error
+        error: signature::Error,
+    },
+    /// The batch was cancelled, after handing over the signatures of the
+    /// first `completed` messages
+    Cancelled {
+        /// Number of signatures handed over
+        completed: usize,
+    },
+}
+
+#[cfg(feature = "sign")]
+impl fmt::Display for SignBatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignBatchError::Signing { index, error } => {
+                write!(f, "Failed to sign message {index} of the batch: {error}")
+            }
+            SignBatchError::Cancelled { completed } => {
+                write!(f, "Batch cancelled after {completed} signature(s)")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "sign")]
+impl core::error::Error for SignBatchError {}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> SigningKey<P> {
+    /// Sign each of `messages`, handing over their signatures to `sink` in
+    /// order, with the index of the message.
+    ///
+    /// With the `rayon` feature, the messages are signed concurrently on the
+    /// pool of `options`, while `sink` runs on the calling thread. At most
+    /// [`max_in_flight`](SignBatchOptions::max_in_flight()) messages are
+    /// being signed or waiting for the previous ones to be handed over:
+    /// the next message starts as soon as a signature is handed over.
+    /// When the calling thread belongs to a pool (e.g., inside `par_iter()`),
+    /// it runs pending jobs while waiting for the next signature. Without
+    /// the `rayon` feature, the messages are signed sequentially.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SignBatchError`] if signing a message fails, or if the
+    /// batch is cancelled. Either way, `sink` got the signatures of all the
+    /// previous messages.
+    pub fn sign_batch<M, F>(
+        &self,
+        messages: &[M],
+        options: &SignBatchOptions<'_>,
+        mut sink: F,
+    ) -> Result<(), SignBatchError>
+    where
+        M: AsRef<[u8]> + Sync,
+        F: FnMut(usize, Signature<P>),
+    {
+        #[cfg(feature = "rayon")]
+        return self.sign_batch_concurrently(messages, options, &mut sink);
+
+        #[cfg(not(feature = "rayon"))]
+        {
+            for (index, message) in messages.iter().enumerate() {
+                hand_over(
+                    index,
+                    self.sign_item(message.as_ref(), options),
+                    options,
+                    &mut sink,
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Sign `message`, or return `None` if the batch was cancelled
+    fn sign_item(&self, message: &[u8], options: &SignBatchOptions<'_>) -> Option<ItemResult<P>> {
+        if options.is_cancelled() {
+            return None;
+        }
+        Some(if options.hedged {
+            self.try_sign_hedged_with_ctx(message, options.context)
+        } else {
+            self.try_sign_with_ctx(message, options.context)
+        })
+    }
+
+    /// Sign the messages on the pool, through a sliding window of
+    /// `max_in_flight` messages, and hand over their signatures in order
+    #[cfg(feature = "rayon")]
+    fn sign_batch_concurrently<M, F>(
+        &self,
+        messages: &[M],
+        options: &SignBatchOptions<'_>,
+        sink: &mut F,
+    ) -> Result<(), SignBatchError>
+    where
+        M: AsRef<[u8]> + Sync,
+        F: FnMut(usize, Signature<P>),
+    {
+        use alloc::collections::BTreeMap;
+        use std::sync::mpsc;
+
+        let window = options.window();
+        // Set once the batch failed: the messages not started yet are
+        // skipped
+        let failed = AtomicBool::new(false);
+        let (sender, receiver) = mpsc::channel();
+
+        in_place_scope(options.pool, |scope| {
+            let start = |index: usize| {
+                let (sender, failed) = (sender.clone(), &failed);
+                let message = messages[index].as_ref();
+                scope.spawn(move |_| {
+                    let result = if failed.load(Ordering::Relaxed) {
+                        None
+                    } else {
+                        self.sign_item(message, options)
+                    };
+                    // Nobody waits for it anymore once the batch failed
+                    let _ = sender.send((index, result));
+                });
+            };
+
+            let mut started = messages.len().min(window);
+            (0..started).for_each(start);
+            // Signatures produced ahead of the next one to hand over
+            let mut ready = BTreeMap::new();
+            let mut next = 0;
+            let outcome = loop {
+                if let Some(result) = ready.remove(&next) {
+                    if let Err(e) = hand_over(next, result, options, sink) {
+                        break Err(e);
+                    }
+                    next += 1;
+                    if started < messages.len() {
+                        start(started);
+                        started += 1;
+                    }
+                } else if next == messages.len() {
+                    break Ok(());
+                } else {
+                    // A started message is pending
+                    let (index, result) = wait_for(&receiver);
+                    ready.insert(index, result);
+                }
+            };
+            if outcome.is_err() {
+                failed.store(true, Ordering::Relaxed);
+            }
+            outcome
+        })
+    }
+}
+
+/// Receive the outcome of a message being signed.
+///
+/// On a thread of a pool, the spawned jobs may sit in its own queue: run
+/// pending jobs meanwhile instead of blocking, which would deadlock a pool
+/// without other threads to run them.
+#[cfg(all(feature = "sign", feature = "rayon"))]
+fn wait_for<T>(receiver: &std::sync::mpsc::Receiver<T>) -> T {
+    use std::sync::mpsc::TryRecvError;
+
+    loop {
+        match receiver.try_recv() {
+            Ok(received) => return received,
+            Err(TryRecvError::Disconnected) => unreachable!("`sender` is alive"),
+            Err(TryRecvError::Empty) => {}
+        }
+        match rayon::yield_now() {
+            Some(rayon::Yield::Executed) => {}
+            // The other threads of the pool took the jobs: check back for
+            // new jobs now and then
+            Some(rayon::Yield::Idle) => {
+                if let Ok(received) = receiver.recv_timeout(std::time::Duration::from_millis(1)) {
+                    return received;
+                }
+            }
+            // Not a thread of a pool: the pool runs the jobs
+            None => return receiver.recv().expect("`sender` is alive"),
+        }
+    }
+}
+
+/// Run `op` in a scope of `pool`, or of the global thread pool, on the
+/// calling thread
+#[cfg(all(feature = "sign", feature = "rayon"))]
+fn in_place_scope<'scope, R>(
+    pool: Option<&rayon::ThreadPool>,
+    op: impl FnOnce(&rayon::Scope<'scope>) -> R,
+) -> R {
+    match pool {
+        Some(pool) => pool.in_place_scope(op),
+        None => rayon::in_place_scope(op),
+    }
+}
+
+/// Hand over the signature of the message at `index` to `sink`, or turn the
+/// outcome of signing it into an error
+#[cfg(feature = "sign")]
+fn hand_over<P: ParameterSet>(
+    index: usize,
+    result: Option<ItemResult<P>>,
+    options: &SignBatchOptions<'_>,
+    sink: &mut impl FnMut(usize, Signature<P>),
+) -> Result<(), SignBatchError> {
+    if options.is_cancelled() {
+        return Err(SignBatchError::Cancelled { completed: index });
+    }
+    match result {
+        None => Err(SignBatchError::Cancelled { completed: index }),
+        Some(Err(error)) => Err(SignBatchError::Signing { index, error }),
+        Some(Ok(sig)) => {
+            sink(index, sig);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sign", slhdsa_fast_sets))]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::wrapper::utils::macros::{gen_test, test_fast_parameter_sets};
+    use crate::*;
+    use alloc::vec;
+
+    fn test_verify_batch<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let (_, other_vk) = keygen::<P>().expect("Keygen failed");
+        let ctx = b"batch";
+
+        let mut batch: Vec<_> = (0u8..6)
+            .map(|i| {
+                let msg = vec![i; 32];
+                let sig = sk.try_sign_with_ctx(&msg, ctx).expect("Signing failed");
+                (vk.clone(), msg, ctx.to_vec(), sig)
+            })
+            .collect();
+        assert_eq!(verify_batch(&batch), Ok(()));
+        assert_eq!(verify_batch::<BatchEntry<'_, P>>(&[]), Ok(()));
+
+        batch[1].1[0] ^= 1; // Message
+        batch[3].2.clear(); // Context
+        batch[4].0 = other_vk; // Key
+        let err = verify_batch(&batch).unwrap_err();
+        assert_eq!(err.failed(), &[1, 3, 4]);
+    }
+    test_fast_parameter_sets!(test_verify_batch);
+
+    fn test_sign_batch<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let messages: Vec<Vec<u8>> = (0u8..5).map(|i| vec![i; 32]).collect();
+        let ctx = b"batch";
+
+        let mut signatures = Vec::new();
+        let options = SignBatchOptions::new().context(ctx).max_in_flight(2);
+        sk.sign_batch(&messages, &options, |i, sig| signatures.push((i, sig)))
+            .expect("Signing failed");
+        assert_eq!(signatures.len(), messages.len());
+        for (expected, (i, sig)) in signatures.iter().enumerate() {
+            assert_eq!(*i, expected);
+            let msg = &messages[expected];
+            assert_eq!(*sig, sk.try_sign_with_ctx(msg, ctx).unwrap());
+        }
+
+        let options = options.hedged(true);
+        sk.sign_batch(&messages, &options, |i, sig| {
+            vk.verify_with_ctx(&messages[i], ctx, &sig)
+                .expect("Verification failed");
+            assert_ne!(sig, signatures[i].1);
+        })
+        .expect("Signing failed");
+    }
+
+    #[cfg(feature = "shake-128f")]
+    fn test_sign_batch_cancel<P: ParameterSet>() {
+        let sk = SigningKey::<P>::new().expect("Keygen failed");
+        let messages: Vec<Vec<u8>> = (0u8..5).map(|i| vec![i; 32]).collect();
+        let cancel = AtomicBool::new(false);
+
+        let options = SignBatchOptions::new().max_in_flight(2).cancel_on(&cancel);
+        let mut count = 0;
+        let err = sk
+            .sign_batch(&messages, &options, |i, _| {
+                count += 1;
+                if i == 1 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            })
+            .unwrap_err();
+        assert!(matches!(err, SignBatchError::Cancelled { completed: 2 }));
+        assert_eq!(count, 2);
+    }
+
+    test_fast_parameter_sets!(test_sign_batch);
+    #[cfg(feature = "shake-128f")]
+    gen_test!(test_sign_batch_cancel, SLH_DSA_SHAKE_128f);
+
+    #[cfg(all(feature = "rayon", feature = "shake-128f"))]
+    #[test]
+    fn test_sign_batch_pool() {
+        let sk = SigningKey::<SLH_DSA_SHAKE_128f>::new().expect("Keygen failed");
+        let messages: Vec<[u8; 1]> = (0u8..8).map(|i| [i]).collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .expect("Failed to build the thread pool");
+
+        let options = SignBatchOptions::new().pool(&pool);
+        let mut next = 0;
+        sk.sign_batch(&messages, &options, |i, sig| {
+            assert_eq!(i, next);
+            assert_eq!(sig, sk.try_sign_with_ctx(&messages[i], b"").unwrap());
+            next += 1;
+        })
+        .expect("Signing failed");
+        assert_eq!(next, messages.len());
+    }
+
+    /// Signing from the only thread of a pool runs the jobs on that thread
+    #[cfg(all(feature = "rayon", feature = "shake-128f"))]
+    #[test]
+    fn test_sign_batch_single_thread_pool() {
+        let sk = SigningKey::<SLH_DSA_SHAKE_128f>::new().expect("Keygen failed");
+        let messages: Vec<[u8; 1]> = (0u8..4).map(|i| [i]).collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .expect("Failed to build the thread pool");
+
+        for options in [SignBatchOptions::new(), SignBatchOptions::new().pool(&pool)] {
+            let mut count = 0;
+            pool.install(|| sk.sign_batch(&messages, &options, |_, _| count += 1))
+                .expect("Signing failed");
+            assert_eq!(count, messages.len());
+        }
+    }
+
+    #[cfg(all(feature = "sha2-128f", feature = "shake-128f"))]
+    #[test]
+    fn test_verify_batch_mixed() {
+        use alloc::boxed::Box;
+
+        let (sha2_sk, sha2_vk) = keygen::<SLH_DSA_SHA2_128f>().expect("Keygen failed");
+        let (shake_sk, shake_vk) = keygen::<SLH_DSA_SHAKE_128f>().expect("Keygen failed");
+        let msg = b"Hello, world!";
+        let sha2_sig = sha2_sk.sign(msg);
+        let shake_sig = shake_sk.sign(msg);
+
+        let batch: Vec<Box<dyn BatchItem + Send + Sync + '_>> = vec![
+            Box::new(BatchEntry::new(&sha2_vk, msg, b"", &sha2_sig)),
+            Box::new(BatchEntry::new(&shake_vk, msg, b"", &shake_sig)),
+            Box::new(BatchEntry::new(&shake_vk, b"Goodbye", b"", &shake_sig)),
+        ];
+        let err = verify_batch(&batch).unwrap_err();
+        assert_eq!(err.failed(), &[2]);
+        assert_eq!(
+            std::string::ToString::to_string(&err),
+            "1 signature(s) of the batch failed to verify"
+        );
+    }
+}
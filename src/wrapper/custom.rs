@@ -0,0 +1,704 @@
+//! Custom parameter sets, e.g. the reduced-signature-count sets being
+//! standardized by NIST for firmware signing.
+//!
+//! A parameter set is defined from its FIPS 205 parameters
+//! `(n, h, d, a, k, lg_w)` and hash family with [`custom_parameter_set!`](crate::custom_parameter_set),
+//! and can then be used with [`SigningKey`](crate::SigningKey),
+//! [`VerifyingKey`](crate::VerifyingKey) and [`Signature`](crate::Signature)
+//! like the built-in ones. The matching `slh_param_s` is built by the C
+//! library on first use, starting from the built-in parameter set with the
+//! same hash family and `n`.
+//!
+//! Sets that only allow a limited number of signatures per key can declare
+//! `max_signatures`: signing through a [`CountedSigningKey`] then enforces
+//! this limit, using a [`SignatureCounter`].
+//!
+//! # Usage
+//!
+//! ```rust
+//! use slhdsa_c_rs::*;
+//! use slhdsa_c_rs::custom::{CountedSigningKey, MemoryCounter};
+//!
+//! custom_parameter_set! {
+//!     /// A toy parameter set, allowing 16 signatures per key
+//!     pub struct SLH_DSA_SHAKE_TOY {
+//!         name: "SLH-DSA-SHAKE-TOY",
+//!         family: Shake,
+//!         n: 16, h: 8, d: 2, a: 4, k: 8, lg_w: 4,
+//!         max_signatures: 16,
+//!     }
+//! }
+//!
+//! let (sk, vk) = keygen::<SLH_DSA_SHAKE_TOY>().expect("Keygen failed");
+//! let mut sk = CountedSigningKey::new(sk, MemoryCounter::new(0));
+//!
+//! let msg: &[u8] = b"Firmware image";
+//! let sig = sk.try_sign_with_ctx(msg, b"").expect("Signing failed");
+//! assert!(vk.verify(msg, &sig).is_ok());
+//! assert_eq!(sk.remaining(), Some(15));
+//! ```
+//!
+//! # Limits
+//!
+//! The C code works on fixed-size buffers: `slhdsa-c` is compiled with the
+//! bounds of its `SLH_EXPERIMENTAL` configuration, which allows XMSS trees
+//! of height `h / d <= 20` and FORS trees of height `a <= 24`. Besides,
+//! only `lg_w` 4 and 8 are supported. Parameter sets beyond these limits,
+//! such as reduced-count sets with a single layer of height above 20, are
+//! compile-time errors in [`custom_parameter_set!`](crate::custom_parameter_set).
+//!
+//! These bounds enlarge the stack buffers of signing and verification for
+//! every parameter set, so custom parameter sets are opt-in, with the
+//! `custom` feature. They are not available with the `system` feature, as
+//! building them requires compiling against the private headers of
+//! `slhdsa-c`.
+
+use core::cell::UnsafeCell;
+use core::ffi::{c_char, c_int, c_void, CStr};
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use generic_array::ArrayLength;
+
+use super::utils::typenum::{self, Unsigned};
+use super::{FFIParams, ParameterSet, SignatureLen, SigningKeyLen, VerifyingKeyLen};
+use crate::ffi;
+
+pub use super::HashFamily;
+
+#[doc(hidden)]
+pub use generic_array::typenum as __typenum;
+
+impl HashFamily {
+    /// Whether the C code of this hash family is compiled in, i.e., whether
+    /// one of its built-in parameter sets is enabled
+    #[must_use]
+    pub const fn is_enabled(self) -> bool {
+        match self {
+            HashFamily::Sha2 => cfg!(slhdsa_c_sha2),
+            HashFamily::Shake => cfg!(slhdsa_c_shake),
+        }
+    }
+}
+
+/// The FIPS 205 parameters of a parameter set (Table 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    /// Security parameter, in bytes: 16, 24 or 32
+    pub n: u32,
+    /// Total height of the hypertree
+    pub h: u32,
+    /// Number of layers of the hypertree
+    pub d: u32,
+    /// Height of the FORS trees
+    pub a: u32,
+    /// Number of FORS trees
+    pub k: u32,
+    /// Base-2 logarithm of the Winternitz parameter
+    pub lg_w: u32,
+}
+
+impl Params {
+    /// Number of WOTS+ chains (FIPS 205, Section 5)
+    #[must_use]
+    pub const fn wots_len(&self) -> u32 {
+        let w = 1 << self.lg_w;
+        let len1 = (8 * self.n).div_ceil(self.lg_w);
+        len1 + (len1 * (w - 1)).ilog2() / self.lg_w + 1
+    }
+
+    /// Length of the message digest `m`, in bytes: the FORS indices, the
+    /// tree index and the leaf index (FIPS 205, Section 9.2)
+    #[must_use]
+    pub const fn digest_len(&self) -> u32 {
+        let hp = self.h / self.d;
+        (self.k * self.a).div_ceil(8) + (self.h - hp).div_ceil(8) + hp.div_ceil(8)
+    }
+
+    /// Length of the verifying key, in bytes
+    #[must_use]
+    pub const fn verifying_key_len(&self) -> usize {
+        2 * self.n as usize
+    }
+
+    /// Length of the signing key, in bytes
+    #[must_use]
+    pub const fn signing_key_len(&self) -> usize {
+        4 * self.n as usize
+    }
+
+    /// Length of the signature, in bytes: the randomizer, the FORS signature
+    /// and the hypertree signature (FIPS 205, Section 9)
+    #[must_use]
+    pub const fn signature_len(&self) -> usize {
+        let Params { n, h, d, a, k, .. } = *self;
+        (n * (1 + k * (1 + a) + h + d * self.wots_len())) as usize
+    }
+
+    /// Check that the parameters are supported, including by the fixed-size
+    /// buffers of `slhdsa-c`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if they are not, which is a compile-time error in
+    /// [`custom_parameter_set!`](crate::custom_parameter_set).
+    pub const fn validate(&self) {
+        assert!(
+            matches!(self.n, 16 | 24 | 32),
+            "n must be 16, 24 or 32 bytes"
+        );
+        assert!(
+            self.d > 0 && self.h.is_multiple_of(self.d),
+            "d must divide h"
+        );
+        assert!(self.h / self.d <= 20, "The XMSS trees are too high");
+        assert!(self.h - self.h / self.d <= 64, "The hypertree is too high");
+        assert!(self.a > 0 && self.a <= 24, "a must be in 1..=24");
+        assert!(self.k > 0 && self.k <= 35, "k must be in 1..=35");
+        // `slhdsa-c` computes `len2` with the FIPS 205 formula for `lg_w` 4
+        // and 8 only
+        assert!(matches!(self.lg_w, 4 | 8), "lg_w must be 4 or 8");
+        assert!(self.wots_len() <= 67, "The WOTS+ signatures are too long");
+        assert!(self.digest_len() <= 49, "The message digest is too long");
+    }
+}
+
+/// A parameter set defined by its FIPS 205 parameters, through
+/// [`custom_parameter_set!`](crate::custom_parameter_set).
+///
+/// Every `CustomParameterSet` is a [`ParameterSet`].
+///
+/// # Safety
+///
+/// The C code writes keys and signatures of the sizes given by `PARAMS`
+/// into buffers of the sizes given by the associated types. Implementations
+/// must therefore:
+/// - have `PARAMS` pass [`Params::validate()`],
+/// - have `VerifyingKeySize`, `SigningKeySize` and `SignatureSize` equal
+///   to `PARAMS.verifying_key_len()`, `PARAMS.signing_key_len()` and
+///   `PARAMS.signature_len()`,
+/// - return from `storage()` a [`ParamStorage`] used by no other type.
+///
+/// [`custom_parameter_set!`](crate::custom_parameter_set) checks all of
+/// this at compile time, and is the only supported way of implementing it.
+pub unsafe trait CustomParameterSet: PartialEq + Eq + Clone + fmt::Debug + 'static {
+    /// Name of the parameter set, also returned by `slh_alg_id()`
+    const ALG_ID: &'static CStr;
+
+    /// Object identifier, if any (empty otherwise)
+    const OID: &'static str = "";
+
+    /// Hash family
+    const FAMILY: HashFamily;
+
+    /// FIPS 205 parameters
+    const PARAMS: Params;
+
+    /// Number of signatures a key can safely produce, if limited
+    const MAX_SIGNATURES: Option<u64> = None;
+
+    /// `PARAMS.verifying_key_len()` as a type
+    type VerifyingKeySize: ArrayLength;
+
+    /// `PARAMS.signing_key_len()` as a type
+    type SigningKeySize: ArrayLength;
+
+    /// `PARAMS.signature_len()` as a type
+    type SignatureSize: ArrayLength;
+
+    #[doc(hidden)]
+    fn storage() -> &'static ParamStorage;
+}
+
+/// Length of the WOTS+ signatures, in chains, for `N` and `LgW`
+#[doc(hidden)]
+pub type WotsLen<N, LgW> = typenum::Sum<
+    WotsLen1<N, LgW>,
+    typenum::Add1<
+        typenum::Quot<
+            typenum::Log2<
+                typenum::Prod<WotsLen1<N, LgW>, typenum::Sub1<typenum::Exp<typenum::U2, LgW>>>,
+            >,
+            LgW,
+        >,
+    >,
+>;
+
+/// `len1` of FIPS 205 for `N` and `LgW`
+#[doc(hidden)]
+pub type WotsLen1<N, LgW> =
+    typenum::Quot<typenum::Sum<typenum::Prod<typenum::U8, N>, typenum::Sub1<LgW>>, LgW>;
+
+/// [`Params::signature_len()`] as a type
+#[doc(hidden)]
+pub type SignatureLenOf<N, H, D, A, K, LgW> = typenum::Prod<
+    N,
+    typenum::Sum<
+        typenum::Sum<typenum::Sum<typenum::U1, typenum::Prod<K, typenum::Add1<A>>>, H>,
+        typenum::Prod<D, WotsLen<N, LgW>>,
+    >,
+>;
+
+/// Upper bound on `sizeof(slh_param_t)`, set by `build.rs` and checked when
+/// compiling `csrc/custom_params.c`
+const PARAM_STORAGE_LEN: usize =
+    match usize::from_str_radix(env!("SLHDSA_C_RS_PARAM_STORAGE_LEN"), 10) {
+        Ok(len) => len,
+        Err(_) => panic!("Invalid SLHDSA_C_RS_PARAM_STORAGE_LEN"),
+    };
+
+#[repr(C, align(16))]
+struct ParamBuf([u8; PARAM_STORAGE_LEN]);
+
+/// Storage for the `slh_param_s` of a custom parameter set, initialized on
+/// first use.
+#[doc(hidden)]
+pub struct ParamStorage {
+    state: AtomicU8,
+    buf: UnsafeCell<ParamBuf>,
+}
+
+// SAFETY: `buf` is only written once, by the thread moving `state` from
+// `UNINIT` to `INITIALIZING`, and only read after `state` is `READY`.
+unsafe impl Sync for ParamStorage {}
+
+impl ParamStorage {
+    const UNINIT: u8 = 0;
+    const INITIALIZING: u8 = 1;
+    const READY: u8 = 2;
+    const FAILED: u8 = 3;
+
+    /// Uninitialized storage
+    #[allow(clippy::new_without_default)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(Self::UNINIT),
+            buf: UnsafeCell::new(ParamBuf([0; PARAM_STORAGE_LEN])),
+        }
+    }
+
+    fn get_or_init<P: CustomParameterSet>(&'static self) -> &'static ffi::slh_param_s {
+        loop {
+            match self.state.compare_exchange_weak(
+                Self::UNINIT,
+                Self::INITIALIZING,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    let ret = init::<P>(self.buf.get());
+                    let state = if ret == 0 { Self::READY } else { Self::FAILED };
+                    self.state.store(state, Ordering::Release);
+                }
+                Err(Self::READY) => break,
+                Err(Self::FAILED) => panic!(
+                    "Cannot build the slh_param_s of {}",
+                    <P as ParameterSet>::NAME
+                ),
+                Err(_) => core::hint::spin_loop(),
+            }
+        }
+
+        // SAFETY: `buf` was initialized by `slhdsa_rs_param_init()`
+        unsafe { &*self.buf.get().cast::<ffi::slh_param_s>() }
+    }
+}
+
+unsafe extern "C" {
+    fn slhdsa_rs_param_init(
+        buf: *mut c_void,
+        buf_len: usize,
+        base: *const ffi::slh_param_t,
+        alg_id: *const c_char,
+        h: u32,
+        d: u32,
+        a: u32,
+        k: u32,
+        lg_w: u32,
+        m: u32,
+    ) -> c_int;
+}
+
+/// The built-in parameter set providing the hash functions for `family`
+/// and `n`, if its hash family is compiled in.
+fn base_params(family: HashFamily, n: u32) -> Option<&'static ffi::slh_param_s> {
+    // SAFETY: taking references to the immutable C statics
+    #[allow(unused_unsafe)] // If no hash family is compiled in
+    unsafe {
+        match (family, n) {
+            #[cfg(slhdsa_c_sha2)]
+            (HashFamily::Sha2, 16) => Some(&ffi::slh_dsa_sha2_128s),
+            #[cfg(slhdsa_c_sha2)]
+            (HashFamily::Sha2, 24) => Some(&ffi::slh_dsa_sha2_192s),
+            #[cfg(slhdsa_c_sha2)]
+            (HashFamily::Sha2, 32) => Some(&ffi::slh_dsa_sha2_256s),
+            #[cfg(slhdsa_c_shake)]
+            (HashFamily::Shake, 16) => Some(&ffi::slh_dsa_shake_128s),
+            #[cfg(slhdsa_c_shake)]
+            (HashFamily::Shake, 24) => Some(&ffi::slh_dsa_shake_192s),
+            #[cfg(slhdsa_c_shake)]
+            (HashFamily::Shake, 32) => Some(&ffi::slh_dsa_shake_256s),
+            _ => None,
+        }
+    }
+}
+
+fn init<P: CustomParameterSet>(buf: *mut ParamBuf) -> c_int {
+    const ERROR: c_int = -1;
+
+    let Some(base) = base_params(P::FAMILY, P::PARAMS.n) else {
+        // No parameter set of this hash family is enabled, which
+        // `custom_parameter_set!` rules out
+        return ERROR;
+    };
+    let Params {
+        h, d, a, k, lg_w, ..
+    } = P::PARAMS;
+    let ret = unsafe {
+        slhdsa_rs_param_init(
+            buf.cast(),
+            PARAM_STORAGE_LEN,
+            base,
+            P::ALG_ID.as_ptr(),
+            h,
+            d,
+            a,
+            k,
+            lg_w,
+            P::PARAMS.digest_len(),
+        )
+    };
+    if ret != 0 {
+        return ret;
+    }
+
+    // The buffers of the wrapper must be the ones the C code expects
+    let prm = buf.cast::<ffi::slh_param_s>().cast_const();
+    // SAFETY: `prm` was initialized by `slhdsa_rs_param_init()`
+    let sizes = unsafe {
+        (
+            ffi::slh_pk_sz(prm),
+            ffi::slh_sk_sz(prm),
+            ffi::slh_sig_sz(prm),
+        )
+    };
+    if sizes
+        != (
+            P::VerifyingKeySize::USIZE,
+            P::SigningKeySize::USIZE,
+            P::SignatureSize::USIZE,
+        )
+    {
+        return ERROR;
+    }
+    0
+}
+
+impl<P: CustomParameterSet> FFIParams for P {
+    const HASH_FAMILY: HashFamily = <P as CustomParameterSet>::FAMILY;
+
+    fn prm() -> &'static ffi::slh_param_s {
+        P::storage().get_or_init::<P>()
+    }
+}
+
+impl<P: CustomParameterSet> SignatureLen for P {
+    const SIGNATURE_LEN: usize = <P as CustomParameterSet>::SignatureSize::USIZE;
+    type LEN = <P as CustomParameterSet>::SignatureSize;
+}
+
+impl<P: CustomParameterSet> SigningKeyLen for P {
+    const SIGNING_KEY_LEN: usize = <P as CustomParameterSet>::SigningKeySize::USIZE;
+    type LEN = <P as CustomParameterSet>::SigningKeySize;
+}
+
+impl<P: CustomParameterSet> VerifyingKeyLen for P {
+    const VERIFYING_KEY_LEN: usize = <P as CustomParameterSet>::VerifyingKeySize::USIZE;
+    type LEN = <P as CustomParameterSet>::VerifyingKeySize;
+}
+
+impl<P: CustomParameterSet> ParameterSet for P {
+    const NAME: &'static str = match core::str::from_utf8(P::ALG_ID.to_bytes()) {
+        Ok(name) => name,
+        Err(_) => panic!("The parameter set name must be valid UTF-8"),
+    };
+    const ALGORITHM_OID_STR: &'static str = P::OID;
+}
+
+/// Define a [`CustomParameterSet`] from its FIPS 205 parameters.
+///
+/// The key and signature sizes are derived from the parameters at compile
+/// time, and unsupported parameters are compile-time errors.
+///
+/// See the [`custom`](crate::custom) module for an example.
+#[macro_export]
+macro_rules! custom_parameter_set {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $ident:ident {
+            name: $name:literal,
+            family: $family:ident,
+            n: $n:literal, h: $h:literal, d: $d:literal, a: $a:literal, k: $k:literal, lg_w: $lg_w:literal
+            $(, oid: $oid:literal)?
+            $(, max_signatures: $max:expr)?
+            $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[allow(non_camel_case_types)]
+        #[derive(PartialEq, Eq, Clone, Debug)]
+        $vis struct $ident {}
+
+        // SAFETY: the sizes and parameters are checked below
+        unsafe impl $crate::custom::CustomParameterSet for $ident {
+            const ALG_ID: &'static ::core::ffi::CStr =
+                match ::core::ffi::CStr::from_bytes_with_nul(concat!($name, "\0").as_bytes()) {
+                    Ok(name) => name,
+                    Err(_) => panic!("The parameter set name must not contain NUL bytes"),
+                };
+            $(const OID: &'static str = $oid;)?
+            const FAMILY: $crate::custom::HashFamily = $crate::custom::HashFamily::$family;
+            const PARAMS: $crate::custom::Params = {
+                let params = $crate::custom::Params {
+                    n: $n,
+                    h: $h,
+                    d: $d,
+                    a: $a,
+                    k: $k,
+                    lg_w: $lg_w,
+                };
+                params.validate();
+                params
+            };
+            $(const MAX_SIGNATURES: Option<u64> = Some($max);)?
+
+            type VerifyingKeySize = $crate::custom::__typenum::Prod<
+                $crate::custom::__typenum::U2,
+                $crate::custom::__typenum::U<$n>,
+            >;
+            type SigningKeySize = $crate::custom::__typenum::Prod<
+                $crate::custom::__typenum::U4,
+                $crate::custom::__typenum::U<$n>,
+            >;
+            type SignatureSize = $crate::custom::SignatureLenOf<
+                $crate::custom::__typenum::U<$n>,
+                $crate::custom::__typenum::U<$h>,
+                $crate::custom::__typenum::U<$d>,
+                $crate::custom::__typenum::U<$a>,
+                $crate::custom::__typenum::U<$k>,
+                $crate::custom::__typenum::U<$lg_w>,
+            >;
+
+            fn storage() -> &'static $crate::custom::ParamStorage {
+                static STORAGE: $crate::custom::ParamStorage = $crate::custom::ParamStorage::new();
+                &STORAGE
+            }
+        }
+
+        // Cross-check the type-level sizes with the parameters
+        const _: () = {
+            use $crate::custom::__typenum::Unsigned;
+            use $crate::custom::CustomParameterSet;
+            assert!(
+                <$ident as CustomParameterSet>::FAMILY.is_enabled(),
+                "No parameter set of this hash family is enabled"
+            );
+            let params = <$ident as CustomParameterSet>::PARAMS;
+            assert!(
+                <$ident as CustomParameterSet>::SignatureSize::USIZE == params.signature_len()
+            );
+            assert!(
+                <$ident as CustomParameterSet>::SigningKeySize::USIZE == params.signing_key_len()
+            );
+            assert!(
+                <$ident as CustomParameterSet>::VerifyingKeySize::USIZE
+                    == params.verifying_key_len()
+            );
+        };
+    };
+}
+
+#[cfg(feature = "sign")]
+mod counter;
+#[cfg(feature = "sign")]
+pub use counter::*;
+
+#[cfg(all(test, feature = "sign", slhdsa_c_shake))]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::*;
+
+    #[cfg(feature = "shake-128f")]
+    crate::custom_parameter_set! {
+        /// SLH-DSA-SHAKE-128f under another name
+        struct SHAKE_128F_COPY {
+            name: "SLH-DSA-SHAKE-128f-copy",
+            family: Shake,
+            n: 16, h: 66, d: 22, a: 6, k: 33, lg_w: 4,
+        }
+    }
+
+    crate::custom_parameter_set! {
+        /// A small parameter set, allowing 4 signatures per key
+        struct SHAKE_TOY {
+            name: "SLH-DSA-SHAKE-TOY",
+            family: Shake,
+            n: 16, h: 8, d: 2, a: 4, k: 8, lg_w: 4,
+            max_signatures: 4,
+        }
+    }
+
+    crate::custom_parameter_set! {
+        /// A small parameter set with `w = 256`
+        struct SHAKE_TOY_W256 {
+            name: "SLH-DSA-SHAKE-TOY-W256",
+            family: Shake,
+            n: 16, h: 8, d: 2, a: 4, k: 8, lg_w: 8,
+        }
+    }
+
+    crate::custom_parameter_set! {
+        /// A single-layer parameter set, beyond the FIPS 205 bounds of
+        /// `h / d` and `a`
+        struct SHAKE_TOY_TALL {
+            name: "SLH-DSA-SHAKE-TOY-TALL",
+            family: Shake,
+            n: 16, h: 10, d: 1, a: 16, k: 2, lg_w: 4,
+        }
+    }
+
+    #[test]
+    fn test_params_sizes() {
+        for (params, sig_len, digest_len) in [
+            (
+                Params {
+                    n: 16,
+                    h: 63,
+                    d: 7,
+                    a: 12,
+                    k: 14,
+                    lg_w: 4,
+                },
+                7856,
+                30,
+            ),
+            (
+                Params {
+                    n: 32,
+                    h: 68,
+                    d: 17,
+                    a: 9,
+                    k: 35,
+                    lg_w: 4,
+                },
+                49856,
+                49,
+            ),
+        ] {
+            params.validate();
+            assert_eq!(params.signature_len(), sig_len);
+            assert_eq!(params.digest_len(), digest_len);
+        }
+    }
+
+    #[test]
+    fn test_params_rejected() {
+        let toy = Params {
+            n: 16,
+            h: 8,
+            d: 2,
+            a: 4,
+            k: 8,
+            lg_w: 4,
+        };
+        for params in [
+            // 68 WOTS+ chains
+            Params { lg_w: 2, ..toy },
+            Params { lg_w: 1, ..toy },
+            Params { n: 20, ..toy },
+            Params { h: 21, d: 1, ..toy },
+            Params { a: 25, ..toy },
+            Params { k: 36, ..toy },
+        ] {
+            let result = std::panic::catch_unwind(|| params.validate());
+            assert!(result.is_err(), "{params:?} accepted");
+        }
+    }
+
+    #[test]
+    fn test_param_storage_len() {
+        unsafe extern "C" {
+            fn slhdsa_rs_param_size() -> usize;
+        }
+
+        // SAFETY: no preconditions
+        let size = unsafe { slhdsa_rs_param_size() };
+        assert!(size <= PARAM_STORAGE_LEN, "sizeof(slh_param_t) = {size}");
+    }
+
+    #[test]
+    fn test_custom_sizes_match_ffi() {
+        assert_eq!(SHAKE_TOY::SIGNATURE_LEN, SHAKE_TOY::signature_len());
+        assert_eq!(SHAKE_TOY::SIGNING_KEY_LEN, SHAKE_TOY::signing_key_len());
+        assert_eq!(SHAKE_TOY::VERIFYING_KEY_LEN, SHAKE_TOY::verifying_key_len());
+        assert_eq!(SHAKE_TOY::NAME, SHAKE_TOY::algorithm_name());
+    }
+
+    /// A custom set with the parameters of a built-in one behaves the same
+    #[cfg(feature = "shake-128f")]
+    #[test]
+    fn test_custom_matches_builtin() {
+        let seeds = [[1u8; 16], [2u8; 16], [3u8; 16]];
+        let (sk, vk) = keygen_from_seeds::<SHAKE_128F_COPY>(&seeds[0], &seeds[1], &seeds[2])
+            .expect("Keygen failed");
+        let (their_sk, their_vk) =
+            keygen_from_seeds::<SLH_DSA_SHAKE_128f>(&seeds[0], &seeds[1], &seeds[2])
+                .expect("Keygen failed");
+        assert_eq!(sk.as_bytes(), their_sk.as_bytes());
+        assert_eq!(vk.as_bytes(), their_vk.as_bytes());
+
+        let msg = b"Hello, world!";
+        let sig = sk.try_sign_with_ctx(msg, b"ctx").expect("Signing failed");
+        let their_sig = their_sk
+            .try_sign_with_ctx(msg, b"ctx")
+            .expect("Signing failed");
+        assert_eq!(sig.as_bytes(), their_sig.as_bytes());
+        assert!(vk.verify_with_ctx(msg, b"ctx", &sig).is_ok());
+    }
+
+    #[test]
+    fn test_custom_sign_verify() {
+        fn sign_verify<P: CustomParameterSet>() {
+            assert_eq!(P::SIGNATURE_LEN, P::signature_len());
+            let (sk, vk) = keygen::<P>().expect("Keygen failed");
+            let msg = b"Hello, world!";
+            let sig = sk.try_sign_with_ctx(msg, b"ctx").expect("Signing failed");
+            assert!(vk.verify_with_ctx(msg, b"ctx", &sig).is_ok());
+            assert!(vk.verify_with_ctx(msg, b"", &sig).is_err());
+        }
+
+        sign_verify::<SHAKE_TOY_W256>();
+        sign_verify::<SHAKE_TOY_TALL>();
+    }
+
+    #[test]
+    fn test_counted_signing_key() {
+        let (sk, vk) = keygen::<SHAKE_TOY>().expect("Keygen failed");
+        let mut sk = CountedSigningKey::new(sk, MemoryCounter::new(1));
+        assert_eq!(sk.remaining(), Some(3));
+
+        let msg = b"Hello, world!";
+        for _ in 0..3 {
+            let sig = sk
+                .try_sign_hedged_with_ctx(msg, b"")
+                .expect("Signing failed");
+            assert!(vk.verify(msg, &sig).is_ok());
+        }
+        assert_eq!(sk.remaining(), Some(0));
+        assert!(sk.try_sign_with_ctx(msg, b"").is_err());
+        assert_eq!(sk.counter().count(), 4);
+    }
+}
@@ -0,0 +1,191 @@
+//! Enforcing the signature limit of reduced-count parameter sets.
+
+use core::fmt;
+
+use rand_core::TryCryptoRng;
+use signature::Keypair;
+
+use super::CustomParameterSet;
+use crate::{ParameterSet, Signature, SigningKey, VerifyingKey};
+
+/// Errors of a [`SignatureCounter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterError {
+    /// The key already produced the maximum number of signatures
+    Exhausted,
+    /// The counter state could not be read or persisted
+    Storage,
+}
+
+impl fmt::Display for CounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CounterError::Exhausted => write!(f, "The signature limit of the key is reached"),
+            CounterError::Storage => write!(f, "Failed to update the signature counter"),
+        }
+    }
+}
+
+impl core::error::Error for CounterError {}
+
+/// Number of signatures produced by a key.
+///
+/// For the signature limit to be meaningful, the counter must survive
+/// restarts and never go backwards (e.g., backed by a monotonic counter in
+/// flash or in a secure element), and be bound to a single key.
+pub trait SignatureCounter {
+    /// Number of signatures produced so far
+    fn count(&self) -> u64;
+
+    /// Reserve the next signature if fewer than `limit` were produced, and
+    /// return its index.
+    ///
+    /// The new count must be persisted before returning: the signature is
+    /// only produced afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CounterError::Exhausted`] if `limit` signatures were
+    /// already produced, or [`CounterError::Storage`] if the new count could
+    /// not be persisted.
+    fn try_increment(&mut self, limit: u64) -> Result<u64, CounterError>;
+}
+
+/// A [`SignatureCounter`] kept in memory.
+///
+/// This is only suitable for keys that do not outlive the process, or if
+/// the count is persisted by other means (see [`MemoryCounter::count()`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryCounter {
+    count: u64,
+}
+
+impl MemoryCounter {
+    /// A counter starting from `count` signatures already produced
+    pub fn new(count: u64) -> Self {
+        Self { count }
+    }
+}
+
+impl SignatureCounter for MemoryCounter {
+    fn count(&self) -> u64 {
+        self.count
+    }
+
+    fn try_increment(&mut self, limit: u64) -> Result<u64, CounterError> {
+        if self.count >= limit {
+            return Err(CounterError::Exhausted);
+        }
+        let index = self.count;
+        self.count += 1;
+        Ok(index)
+    }
+}
+
+/// A [`SigningKey`] refusing to produce more than
+/// [`CustomParameterSet::MAX_SIGNATURES`] signatures.
+///
+/// The counter is incremented before signing, so a failed signing operation
+/// still consumes one signature: the limit is never exceeded.
+pub struct CountedSigningKey<P: CustomParameterSet, C: SignatureCounter> {
+    sk: SigningKey<P>,
+    counter: C,
+}
+
+impl<P: CustomParameterSet, C: SignatureCounter> CountedSigningKey<P, C> {
+    /// Count the signatures of `sk` with `counter`
+    pub fn new(sk: SigningKey<P>, counter: C) -> Self {
+        Self { sk, counter }
+    }
+
+    /// The verifying key
+    pub fn verifying_key(&self) -> VerifyingKey<P> {
+        self.sk.verifying_key()
+    }
+
+    /// The signature counter
+    pub fn counter(&self) -> &C {
+        &self.counter
+    }
+
+    /// Number of signatures the key can still produce, if limited
+    pub fn remaining(&self) -> Option<u64> {
+        P::MAX_SIGNATURES.map(|max| max.saturating_sub(self.counter.count()))
+    }
+
+    /// Split into the signing key and its counter
+    pub fn into_parts(self) -> (SigningKey<P>, C) {
+        (self.sk, self.counter)
+    }
+
+    fn reserve(&mut self) -> Result<(), signature::Error> {
+        let limit = P::MAX_SIGNATURES.unwrap_or(u64::MAX);
+        self.counter
+            .try_increment(limit)
+            .map_err(|_| signature::Error::new())?;
+        Ok(())
+    }
+
+    /// Deterministically sign `message` under `context`, see
+    /// [`SigningKey::try_sign_with_ctx()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if the signature limit is reached (see
+    /// [`Self::remaining()`]), if the counter fails, or if signing fails.
+    pub fn try_sign_with_ctx(
+        &mut self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        self.reserve()?;
+        self.sk.try_sign_with_ctx(message, context)
+    }
+
+    /// Produce a hedged signature of `message` under `context`, see
+    /// [`SigningKey::try_sign_hedged_with_ctx()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if the signature limit is reached (see
+    /// [`Self::remaining()`]), if the counter fails, or if signing fails.
+    pub fn try_sign_hedged_with_ctx(
+        &mut self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        self.reserve()?;
+        self.sk.try_sign_hedged_with_ctx(message, context)
+    }
+}
+
+impl<P: CustomParameterSet, C: SignatureCounter> signature::SignerMut<Signature<P>>
+    for CountedSigningKey<P, C>
+{
+    fn try_sign(&mut self, msg: &[u8]) -> Result<Signature<P>, signature::Error> {
+        self.try_sign_with_ctx(msg, crate::wrapper::EMPTY_CTX)
+    }
+}
+
+impl<P: CustomParameterSet, C: SignatureCounter> signature::RandomizedSignerMut<Signature<P>>
+    for CountedSigningKey<P, C>
+{
+    fn try_sign_with_rng<R: TryCryptoRng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        msg: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        self.reserve()?;
+        self.sk
+            .try_sign_with_ctx_and_rng(msg, crate::wrapper::EMPTY_CTX, rng)
+    }
+}
+
+impl<P: CustomParameterSet, C: SignatureCounter> fmt::Debug for CountedSigningKey<P, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CountedSigningKey")
+            .field("parameter_set", &<P as ParameterSet>::NAME)
+            .field("count", &self.counter.count())
+            .finish_non_exhaustive()
+    }
+}
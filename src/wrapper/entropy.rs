@@ -0,0 +1,243 @@
+//! Pluggable entropy sources for key generation and hedged signing.
+//!
+//! Whenever fresh randomness is needed (i.e., the seeds of a new key, or the
+//! `addrnd` value of a hedged signature) the wrapper asks this module to
+//! fill a buffer, using the first available of:
+//!
+//! 1. a source registered at runtime, either a raw fill function (see
+//!    [`set_fill_fn`]) or a user-provided [`TryCryptoRng`] (see [`set_rng`]);
+//! 2. the OS entropy source through [`getrandom`], if the `getrandom`
+//!    feature is enabled;
+//! 3. the OS entropy source through `rand::rngs::SysRng`, if the `rand`
+//!    feature is enabled.
+//!
+//! On bare-metal targets, where neither of the default backends is
+//! available, applications are expected to register their own source
+//! (e.g., backed by a hardware TRNG) before generating keys. If no source
+//! is available at all, or if the source fails, key generation fails with
+//! [`KeygenError::Entropy`](crate::KeygenError::Entropy) rather than
+//! silently producing predictable keys.
+//!
+//! [`getrandom`]: https://docs.rs/getrandom
+//!
+//! # Usage
+//!
+//! ```rust
+//! use slhdsa_c_rs::entropy::{self, EntropyError};
+//! use slhdsa_c_rs::*;
+//!
+//! fn trng_fill(buf: &mut [u8]) -> Result<(), EntropyError> {
+//!     // Read from the hardware TRNG here...
+//!     # let _ = buf;
+//!     # return Err(EntropyError::SourceFailure);
+//! }
+//!
+//! entropy::set_fill_fn(trng_fill);
+//! assert_eq!(
+//!     keygen::<SLH_DSA_SHAKE_128s>().unwrap_err(),
+//!     KeygenError::Entropy(EntropyError::SourceFailure)
+//! );
+//!
+//! // Go back to the feature-selected default backend
+//! entropy::reset();
+//! assert!(keygen::<SLH_DSA_SHAKE_128s>().is_ok());
+//! ```
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+pub use rand_core::TryCryptoRng;
+
+/// Signature of a raw entropy fill function, as accepted by [`set_fill_fn`].
+///
+/// Implementations must either fill the whole buffer with
+/// cryptographically secure random bytes and return `Ok(())`, or return an
+/// error.
+pub type FillFn = fn(&mut [u8]) -> Result<(), EntropyError>;
+
+/// Errors that can occur while gathering entropy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntropyError {
+    /// No entropy source was registered, and no default backend is enabled.
+    Unavailable,
+    /// The entropy source reported a failure.
+    SourceFailure,
+}
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntropyError::Unavailable => write!(f, "No entropy source available"),
+            EntropyError::SourceFailure => write!(f, "The entropy source failed"),
+        }
+    }
+}
+
+impl core::error::Error for EntropyError {}
+
+/// Object-safe view over a user-provided RNG.
+trait DynRng: Send {
+    fn fill(&mut self, buf: &mut [u8]) -> Result<(), EntropyError>;
+}
+
+impl<R: TryCryptoRng + Send> DynRng for R {
+    fn fill(&mut self, buf: &mut [u8]) -> Result<(), EntropyError> {
+        self.try_fill_bytes(buf)
+            .map_err(|_| EntropyError::SourceFailure)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Source {
+    Default,
+    FillFn(FillFn),
+    /// Leaked from the `&'static mut` given to [`set_rng`], and only
+    /// dereferenced while holding [`RNG_LOCK`]
+    Rng(NonNull<dyn DynRng>),
+}
+
+/// A minimal spinlock.
+///
+/// This is `no_std`, so we cannot rely on `std::sync::Mutex`.
+struct SpinLock(AtomicBool);
+
+/// Releases the [`SpinLock`] on drop, including while unwinding from a
+/// panicking entropy source.
+struct SpinLockGuard<'a>(&'a SpinLock);
+
+impl Drop for SpinLockGuard<'_> {
+    fn drop(&mut self) {
+        (self.0).0.store(false, Ordering::Release);
+    }
+}
+
+impl SpinLock {
+    const fn new() -> Self {
+        Self(AtomicBool::new(false))
+    }
+
+    fn lock(&self) -> SpinLockGuard<'_> {
+        while self
+            .0
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        SpinLockGuard(self)
+    }
+}
+
+/// Global registry for the entropy source.
+///
+/// The lock is only held to read or replace the source, never while the
+/// source runs: fill functions run concurrently, and may themselves
+/// register another source.
+struct Registry {
+    lock: SpinLock,
+    source: UnsafeCell<Source>,
+}
+
+// SAFETY: all accesses to `source` go through `Registry::with()`, which
+// holds the spinlock for the whole duration of the access, and the stored
+// RNG is `Send` and only used while holding `RNG_LOCK`.
+unsafe impl Sync for Registry {}
+
+impl Registry {
+    const fn new() -> Self {
+        Self {
+            lock: SpinLock::new(),
+            source: UnsafeCell::new(Source::Default),
+        }
+    }
+
+    fn with<T>(&self, f: impl FnOnce(&mut Source) -> T) -> T {
+        let _guard = self.lock.lock();
+
+        // SAFETY: we hold the lock until `_guard` is dropped.
+        let source = unsafe { &mut *self.source.get() };
+        f(source)
+    }
+}
+
+static REGISTRY: Registry = Registry::new();
+
+/// Serializes the uses of the registered RNG, which needs exclusive access.
+///
+/// Unlike [`REGISTRY`], it is held while the RNG runs: an RNG calling
+/// [`fill`] itself would deadlock.
+static RNG_LOCK: SpinLock = SpinLock::new();
+
+/// Register a raw fill function as the entropy source.
+///
+/// This replaces any previously registered source.
+pub fn set_fill_fn(f: FillFn) {
+    REGISTRY.with(|source| *source = Source::FillFn(f));
+}
+
+/// Register a user-provided [`TryCryptoRng`] as the entropy source.
+///
+/// Infallible RNGs (i.e., any `CryptoRng`) are accepted as well, as they
+/// implement `TryCryptoRng` with `Error = Infallible`.
+///
+/// The RNG must live for the rest of the program (e.g., a `static` or a
+/// leaked allocation), as it can be used by any later key generation or
+/// hedged signing operation. Concurrent operations take turns to use it,
+/// and it must not call [`fill`] itself.
+///
+/// This replaces any previously registered source.
+pub fn set_rng<R: TryCryptoRng + Send + 'static>(rng: &'static mut R) {
+    let rng: &'static mut dyn DynRng = rng;
+    REGISTRY.with(|source| *source = Source::Rng(NonNull::from(rng)));
+}
+
+/// Drop any registered entropy source, going back to the default backend
+/// selected through cargo features.
+pub fn reset() {
+    REGISTRY.with(|source| *source = Source::Default);
+}
+
+/// Fill `buf` with random bytes from the current entropy source.
+///
+/// If the source panics, the panic propagates to the caller.
+///
+/// # Errors
+///
+/// Returns an [`EntropyError`] if no entropy source is available, or if the
+/// entropy source fails.
+pub fn fill(buf: &mut [u8]) -> Result<(), EntropyError> {
+    match REGISTRY.with(|source| *source) {
+        Source::Default => default_fill(buf),
+        Source::FillFn(f) => f(buf),
+        Source::Rng(mut rng) => {
+            let _guard = RNG_LOCK.lock();
+            // SAFETY: the RNG was leaked from a `&'static mut`, so it is
+            // still alive even if it was replaced meanwhile, and we hold
+            // `RNG_LOCK` until `_guard` is dropped.
+            unsafe { rng.as_mut() }.fill(buf)
+        }
+    }
+}
+
+#[cfg(feature = "getrandom")]
+fn default_fill(buf: &mut [u8]) -> Result<(), EntropyError> {
+    getrandom::fill(buf).map_err(|_| EntropyError::SourceFailure)
+}
+
+#[cfg(all(feature = "rand", not(feature = "getrandom")))]
+fn default_fill(buf: &mut [u8]) -> Result<(), EntropyError> {
+    use rand_core::TryRng;
+
+    // Through the fallible interface, so that a broken OS entropy source is
+    // reported instead of panicking (or being ignored).
+    rand::rngs::SysRng
+        .try_fill_bytes(buf)
+        .map_err(|_| EntropyError::SourceFailure)
+}
+
+#[cfg(not(any(feature = "getrandom", feature = "rand")))]
+fn default_fill(_buf: &mut [u8]) -> Result<(), EntropyError> {
+    Err(EntropyError::Unavailable)
+}
@@ -0,0 +1,197 @@
+//! Deterministic random bit generator for reproducing the NIST KAT files.
+//!
+//! The known-answer test (`.rsp`) files published alongside the NIST PQC
+//! submissions (and the SLH-DSA KATs derived from them) are generated using
+//! the AES-256 `CTR_DRBG` (without derivation function) implemented by
+//! `rng.c` in the NIST reference package.
+//!
+//! [`KatDrbg`] reproduces `randombytes_init()` and `randombytes()` from that
+//! file, and implements [`TryCryptoRng`], so it can be plugged into
+//! [`keygen_with_rng()`](crate::keygen_with_rng) and into the randomized
+//! signing APIs of [`SigningKey`](crate::SigningKey).
+//!
+//! **This is only meant for testing**: the KAT seeds are public, so keys and
+//! signatures generated through [`KatDrbg`] must never be used in practice.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use slhdsa_c_rs::kat::KatDrbg;
+//! use slhdsa_c_rs::*;
+//! use SLH_DSA_SHAKE_128s as P;
+//!
+//! // `seed` as found in the `count = 0` entry of any `.rsp` file
+//! let mut entropy_input = [0u8; 48];
+//! for (i, b) in entropy_input.iter_mut().enumerate() {
+//!     *b = i as u8;
+//! }
+//! let mut seed = [0u8; 48];
+//! KatDrbg::new(&entropy_input, None).fill(&mut seed);
+//!
+//! let mut drbg = KatDrbg::new(&seed, None);
+//! let (sk, vk) = keygen_with_rng::<P, _>(&mut drbg).expect("Keygen failed");
+//!
+//! let msg = b"Hello, world!";
+//! let sig = sk.try_sign_with_rng(&mut drbg, msg).expect("Signing failed");
+//! assert!(vk.verify(msg, &sig).is_ok());
+//! ```
+
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes256;
+use core::convert::Infallible;
+use core::fmt;
+
+pub use rand_core::{TryCryptoRng, TryRng};
+
+/// Length of the `entropy_input` and `personalization_string` arguments of
+/// `randombytes_init()`.
+pub const SEED_LEN: usize = 48;
+
+const KEY_LEN: usize = 32;
+const BLOCK_LEN: usize = 16;
+
+/// AES-256 `CTR_DRBG`, as used by the NIST PQC KAT generators.
+#[derive(Clone)]
+pub struct KatDrbg {
+    key: [u8; KEY_LEN],
+    v: [u8; BLOCK_LEN],
+    reseed_counter: u64,
+}
+
+// Don't leak the DRBG state in debug output
+impl fmt::Debug for KatDrbg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KatDrbg")
+            .field("reseed_counter", &self.reseed_counter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KatDrbg {
+    /// Instantiate the DRBG, equivalent to `randombytes_init()` in the NIST
+    /// reference `rng.c`.
+    pub fn new(entropy_input: &[u8; SEED_LEN], personalization: Option<&[u8; SEED_LEN]>) -> Self {
+        let mut seed_material = *entropy_input;
+        if let Some(personalization) = personalization {
+            for (s, p) in seed_material.iter_mut().zip(personalization) {
+                *s ^= p;
+            }
+        }
+
+        let mut drbg = Self {
+            key: [0u8; KEY_LEN],
+            v: [0u8; BLOCK_LEN],
+            reseed_counter: 1,
+        };
+        drbg.update(Some(&seed_material));
+        drbg
+    }
+
+    /// Fill `out` with pseudo-random bytes, equivalent to `randombytes()` in
+    /// the NIST reference `rng.c`.
+    ///
+    /// Note that, as in the reference implementation, the output depends on
+    /// how requests are split: filling 32 bytes at once does not produce the
+    /// same bytes as filling 16 bytes twice.
+    pub fn fill(&mut self, out: &mut [u8]) {
+        let cipher = Aes256::new(&self.key.into());
+        for chunk in out.chunks_mut(BLOCK_LEN) {
+            self.increment_v();
+            let mut block = aes::Block::from(self.v);
+            cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(None);
+        self.reseed_counter += 1;
+    }
+
+    fn increment_v(&mut self) {
+        for b in self.v.iter_mut().rev() {
+            let (next, overflow) = b.overflowing_add(1);
+            *b = next;
+            if !overflow {
+                break;
+            }
+        }
+    }
+
+    /// `AES256_CTR_DRBG_Update()`
+    fn update(&mut self, provided_data: Option<&[u8; SEED_LEN]>) {
+        let cipher = Aes256::new(&self.key.into());
+
+        let mut temp = [0u8; SEED_LEN];
+        for chunk in temp.chunks_mut(BLOCK_LEN) {
+            self.increment_v();
+            let mut block = aes::Block::from(self.v);
+            cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block);
+        }
+        if let Some(provided_data) = provided_data {
+            for (t, p) in temp.iter_mut().zip(provided_data) {
+                *t ^= p;
+            }
+        }
+
+        self.key.copy_from_slice(&temp[..KEY_LEN]);
+        self.v.copy_from_slice(&temp[KEY_LEN..]);
+    }
+}
+
+impl TryRng for KatDrbg {
+    type Error = Infallible;
+
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        let mut buf = [0u8; 4];
+        self.fill(&mut buf);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        let mut buf = [0u8; 8];
+        self.fill(&mut buf);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn try_fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), Self::Error> {
+        self.fill(dst);
+        Ok(())
+    }
+}
+
+impl TryCryptoRng for KatDrbg {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex<const N: usize>(s: &str) -> [u8; N] {
+        let mut out = [0u8; N];
+        for (i, b) in out.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    /// The first `seed` values found in every NIST PQC `.rsp` file
+    #[test]
+    fn test_kat_seeds() {
+        let mut entropy_input = [0u8; SEED_LEN];
+        for (i, b) in entropy_input.iter_mut().enumerate() {
+            *b = u8::try_from(i).unwrap();
+        }
+        let mut drbg = KatDrbg::new(&entropy_input, None);
+
+        let mut seed = [0u8; SEED_LEN];
+        drbg.fill(&mut seed);
+        assert_eq!(
+            seed,
+            unhex::<SEED_LEN>("061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1")
+        );
+
+        drbg.fill(&mut seed);
+        assert_eq!(
+            seed,
+            unhex::<SEED_LEN>("D81C4D8D734FCBFBEADE3D3F8A039FAA2A2C9957E835AD55B22E75BF57BB556AC81ADDE6AEEB4A5A875C3BFCADFA958F")
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! Amortizing one SLH-DSA signature over many messages.
+//!
+//! An SLH-DSA signature is 7–50 KB long and slow to produce, which is a lot
+//! for small messages such as log entries. A [`MerkleBatch`] instead hashes
+//! the messages into a Merkle tree, and signs its root once. Each message
+//! then gets a [`MerkleProof`]: the shared signature, and the path from the
+//! message to the root (32 bytes per level of the tree).
+//!
+//! The tree is the one of RFC 9162 (Certificate Transparency), with SHA-256:
+//!
+//! - leaves are `SHA-256(0x00 || message)`;
+//! - inner nodes are `SHA-256(0x01 || left || right)`;
+//! - the root is signed with [`SigningKey::try_sign_with_ctx()`] under the
+//!   [`CONTEXT`] context, over `version || tree size || root` (the tree size
+//!   as a 64-bit big-endian integer).
+//!
+//! ```rust
+//! use slhdsa_c_rs::merkle::{MerkleBatch, MerkleProof};
+//! use slhdsa_c_rs::*;
+//! use SLH_DSA_SHAKE_128f as P;
+//!
+//! let (sk, vk) = keygen::<P>().expect("Keygen failed");
+//! let messages: [&[u8]; 3] = [b"first", b"second", b"third"];
+//!
+//! let batch = MerkleBatch::sign(&sk, &messages).expect("Signing failed");
+//! let proof: Vec<u8> = batch.proof(1).to_bytes();
+//!
+//! let proof = MerkleProof::<P>::from_bytes(&proof).expect("Invalid encoding");
+//! assert!(proof.verify(&vk, b"second").is_ok());
+//! assert!(proof.verify(&vk, b"third").is_err());
+//! ```
+//!
+//! # Encoding
+//!
+//! [`MerkleProof::to_bytes()`] produces, in order:
+//!
+//! | Field        | Size                 | Content                                  |
+//! |--------------|----------------------|------------------------------------------|
+//! | `version`    | 1                    | [`VERSION`]                              |
+//! | `tree_size`  | 8                    | Number of messages, big-endian           |
+//! | `index`      | 8                    | Index of the message, big-endian         |
+//! | `path_len`   | 1                    | Number of hashes in the inclusion path   |
+//! | `path`       | `32 * path_len`      | Inclusion path, from the leaf upwards    |
+//! | `signature`  | `P::SIGNATURE_LEN`   | SLH-DSA signature of the root            |
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "sign")]
+use super::SigningKey;
+use super::{ParameterSet, Signature, VerifyingKey};
+
+/// Version of the construction and of its encoding
+pub const VERSION: u8 = 1;
+
+/// Context string the tree roots are signed under
+pub const CONTEXT: &[u8] = b"slhdsa-c-rs/merkle-batch/v1";
+
+/// Size of the hashes of the tree
+pub const HASH_LEN: usize = 32;
+
+/// A node of the tree
+pub type Hash = [u8; HASH_LEN];
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Length of the encoding before the path: version, tree size, index and
+/// path length
+const HEADER_LEN: usize = 1 + 8 + 8 + 1;
+
+/// Errors of Merkle batch signatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MerkleError {
+    /// The batch has no message, or too many
+    BatchSize,
+    /// The encoding uses another version of the construction
+    UnsupportedVersion(u8),
+    /// The encoded proof is malformed
+    Encoding,
+    /// The message is not part of the signed tree
+    InvalidProof,
+    /// Signing the root failed, or its signature is inauthentic
+    Signature,
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerkleError::BatchSize => write!(f, "Invalid number of messages in the batch"),
+            MerkleError::UnsupportedVersion(v) => {
+                write!(f, "Unsupported Merkle batch version {v}")
+            }
+            MerkleError::Encoding => write!(f, "Malformed Merkle proof"),
+            MerkleError::InvalidProof => write!(f, "Invalid Merkle inclusion proof"),
+            MerkleError::Signature => write!(f, "Invalid signature of the Merkle root"),
+        }
+    }
+}
+
+impl core::error::Error for MerkleError {}
+
+fn leaf_hash(message: &[u8]) -> Hash {
+    Sha256::new()
+        .chain_update([LEAF_PREFIX])
+        .chain_update(message)
+        .finalize()
+        .into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    Sha256::new()
+        .chain_update([NODE_PREFIX])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+/// The message whose signature commits to a tree
+fn signed_root(tree_size: u64, root: &Hash) -> [u8; 1 + 8 + HASH_LEN] {
+    let mut msg = [0u8; 1 + 8 + HASH_LEN];
+    msg[0] = VERSION;
+    msg[1..9].copy_from_slice(&tree_size.to_be_bytes());
+    msg[9..].copy_from_slice(root);
+    msg
+}
+
+/// A batch of messages signed at once
+#[derive(Clone, Debug)]
+pub struct MerkleBatch<P: ParameterSet> {
+    /// Each level of the tree, from the leaves to the root. The last node
+    /// of a level without a sibling is promoted to the next level as is.
+    levels: Vec<Vec<Hash>>,
+    signature: Signature<P>,
+}
+
+impl<P: ParameterSet> MerkleBatch<P> {
+    /// Hash `messages` into a tree, and sign its root with `sk`
+    /// (deterministically)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MerkleError::BatchSize`] if `messages` is empty, or
+    /// [`MerkleError::Signature`] if signing fails.
+    #[cfg(feature = "sign")]
+    pub fn sign<M: AsRef<[u8]>>(sk: &SigningKey<P>, messages: &[M]) -> Result<Self, MerkleError> {
+        if messages.is_empty() {
+            return Err(MerkleError::BatchSize);
+        }
+        let tree_size = u64::try_from(messages.len()).map_err(|_| MerkleError::BatchSize)?;
+
+        let mut levels = Vec::new();
+        let mut level: Vec<Hash> = messages.iter().map(|m| leaf_hash(m.as_ref())).collect();
+        while level.len() > 1 {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [last] => *last,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(core::mem::replace(&mut level, next));
+        }
+        let root = level[0];
+        levels.push(level);
+
+        let signature = sk
+            .try_sign_with_ctx(&signed_root(tree_size, &root), CONTEXT)
+            .map_err(|_| MerkleError::Signature)?;
+        Ok(Self { levels, signature })
+    }
+
+    /// Number of messages in the batch
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Whether the batch has no message (never true)
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Root of the tree
+    pub fn root(&self) -> &Hash {
+        &self.levels[self.levels.len() - 1][0]
+    }
+
+    /// The shared signature of the root
+    pub fn signature(&self) -> &Signature<P> {
+        &self.signature
+    }
+
+    /// The proof of the message at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn proof(&self, index: usize) -> MerkleProof<P> {
+        assert!(index < self.len(), "Message index out of bounds");
+
+        let mut path = Vec::with_capacity(self.levels.len() - 1);
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(i ^ 1) {
+                path.push(*sibling);
+            }
+            i >>= 1;
+        }
+        MerkleProof {
+            tree_size: self.len() as u64,
+            index: index as u64,
+            path,
+            signature: self.signature.clone(),
+        }
+    }
+
+    /// The proofs of all the messages, in order
+    pub fn proofs(&self) -> impl Iterator<Item = MerkleProof<P>> + '_ {
+        (0..self.len()).map(|i| self.proof(i))
+    }
+}
+
+/// Proof that a message is part of a signed [`MerkleBatch`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof<P: ParameterSet> {
+    tree_size: u64,
+    index: u64,
+    path: Vec<Hash>,
+    signature: Signature<P>,
+}
+
+impl<P: ParameterSet> MerkleProof<P> {
+    /// Number of messages in the batch
+    pub fn tree_size(&self) -> u64 {
+        self.tree_size
+    }
+
+    /// Index of the message in the batch
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Inclusion path of the message, from the leaf upwards
+    pub fn path(&self) -> &[Hash] {
+        &self.path
+    }
+
+    /// The shared signature of the root
+    pub fn signature(&self) -> &Signature<P> {
+        &self.signature
+    }
+
+    /// Check that `message` is part of a batch signed under `vk`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MerkleError::InvalidProof`] if the path does not lead from
+    /// `message` to a root, or [`MerkleError::Signature`] if the signature
+    /// of the root is inauthentic.
+    pub fn verify(&self, vk: &VerifyingKey<P>, message: &[u8]) -> Result<(), MerkleError> {
+        let root = self.root(&leaf_hash(message))?;
+        vk.verify_with_ctx(
+            &signed_root(self.tree_size, &root),
+            CONTEXT,
+            &self.signature,
+        )
+        .map_err(|_| MerkleError::Signature)
+    }
+
+    /// Recompute the root from the hash of the message, as in Section
+    /// 2.1.3.2 of RFC 9162
+    fn root(&self, leaf: &Hash) -> Result<Hash, MerkleError> {
+        if self.index >= self.tree_size {
+            return Err(MerkleError::InvalidProof);
+        }
+        let mut f_n = self.index;
+        let mut s_n = self.tree_size - 1;
+        let mut r = *leaf;
+        for p in &self.path {
+            if s_n == 0 {
+                return Err(MerkleError::InvalidProof);
+            }
+            if f_n & 1 == 1 || f_n == s_n {
+                r = node_hash(p, &r);
+                while f_n & 1 == 0 && f_n != 0 {
+                    f_n >>= 1;
+                    s_n >>= 1;
+                }
+            } else {
+                r = node_hash(&r, p);
+            }
+            f_n >>= 1;
+            s_n >>= 1;
+        }
+        if s_n != 0 {
+            return Err(MerkleError::InvalidProof);
+        }
+        Ok(r)
+    }
+
+    /// Encode the proof (see the [module documentation](self))
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inclusion path has more than 255 hashes, which is never
+    /// the case for the proofs of a [`MerkleBatch`] (at most 64, since the
+    /// tree size is a `u64`) or decoded by [`Self::from_bytes()`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out =
+            Vec::with_capacity(HEADER_LEN + self.path.len() * HASH_LEN + P::SIGNATURE_LEN);
+        out.push(VERSION);
+        out.extend_from_slice(&self.tree_size.to_be_bytes());
+        out.extend_from_slice(&self.index.to_be_bytes());
+        out.push(u8::try_from(self.path.len()).expect("Path too long"));
+        for hash in &self.path {
+            out.extend_from_slice(hash);
+        }
+        out.extend_from_slice(self.signature.as_ref());
+        out
+    }
+
+    /// Decode a proof produced by [`Self::to_bytes()`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MerkleError::UnsupportedVersion`] if the proof uses another
+    /// version, or [`MerkleError::Encoding`] if it is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleError> {
+        let (&version, rest) = bytes.split_first().ok_or(MerkleError::Encoding)?;
+        if version != VERSION {
+            return Err(MerkleError::UnsupportedVersion(version));
+        }
+        let (tree_size, rest) = rest.split_first_chunk().ok_or(MerkleError::Encoding)?;
+        let (index, rest) = rest.split_first_chunk().ok_or(MerkleError::Encoding)?;
+        let (&path_len, rest) = rest.split_first().ok_or(MerkleError::Encoding)?;
+        let (tree_size, index) = (u64::from_be_bytes(*tree_size), u64::from_be_bytes(*index));
+
+        let path_size = usize::from(path_len) * HASH_LEN;
+        if rest.len() != path_size + P::SIGNATURE_LEN {
+            return Err(MerkleError::Encoding);
+        }
+        let (path, signature) = rest.split_at(path_size);
+        let path = path.as_chunks::<HASH_LEN>().0.to_vec();
+        let signature = Signature::try_from(signature).map_err(|_| MerkleError::Encoding)?;
+
+        Ok(Self {
+            tree_size,
+            index,
+            path,
+            signature,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sign", slhdsa_fast_sets))]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::wrapper::utils::macros::{gen_test, test_fast_parameter_sets};
+    use crate::*;
+    use alloc::vec;
+
+    /// The tree hash of RFC 9162 (Section 2.1.1), computed recursively
+    fn reference_root(leaves: &[Hash]) -> Hash {
+        if leaves.len() == 1 {
+            return leaves[0];
+        }
+        let k = leaves.len().next_power_of_two() / 2;
+        node_hash(&reference_root(&leaves[..k]), &reference_root(&leaves[k..]))
+    }
+
+    fn test_merkle_batch<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let (_, other_vk) = keygen::<P>().expect("Keygen failed");
+
+        for n in [1usize, 2, 3, 5, 8, 13] {
+            let messages: Vec<Vec<u8>> = (0..n).map(|i| vec![0x5a; i + 1]).collect();
+            let batch = MerkleBatch::sign(&sk, &messages).expect("Signing failed");
+            assert_eq!(batch.len(), n);
+
+            let leaves: Vec<Hash> = messages.iter().map(|m| leaf_hash(m)).collect();
+            assert_eq!(batch.root(), &reference_root(&leaves));
+
+            for (i, proof) in batch.proofs().enumerate() {
+                let proof =
+                    MerkleProof::<P>::from_bytes(&proof.to_bytes()).expect("Decoding failed");
+                assert_eq!(proof.index(), i as u64);
+                proof
+                    .verify(&vk, &messages[i])
+                    .expect("Verification failed");
+
+                assert_eq!(
+                    proof.verify(&other_vk, &messages[i]),
+                    Err(MerkleError::Signature)
+                );
+                let other = &messages[(i + 1) % n];
+                if n > 1 {
+                    assert!(proof.verify(&vk, other).is_err());
+                }
+            }
+        }
+        assert_eq!(
+            MerkleBatch::<P>::sign(&sk, &[] as &[&[u8]]).unwrap_err(),
+            MerkleError::BatchSize
+        );
+    }
+
+    #[cfg(feature = "shake-128f")]
+    fn test_merkle_proof_tampering<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let messages: Vec<[u8; 1]> = (0u8..6).map(|i| [i]).collect();
+        let batch = MerkleBatch::sign(&sk, &messages).expect("Signing failed");
+        let proof = batch.proof(4);
+        proof
+            .verify(&vk, &messages[4])
+            .expect("Verification failed");
+
+        // Claim another position or tree size
+        let mut bad = proof.clone();
+        bad.index = 5;
+        assert!(bad.verify(&vk, &messages[4]).is_err());
+        let mut bad = proof.clone();
+        bad.tree_size = 5;
+        assert!(bad.verify(&vk, &messages[4]).is_err());
+        let mut bad = proof.clone();
+        bad.index = 6;
+        assert_eq!(
+            bad.verify(&vk, &messages[4]),
+            Err(MerkleError::InvalidProof)
+        );
+
+        // Alter the path
+        let mut bad = proof.clone();
+        bad.path[0][0] ^= 1;
+        assert!(bad.verify(&vk, &messages[4]).is_err());
+        let mut bad = proof.clone();
+        bad.path.push([0u8; HASH_LEN]);
+        assert_eq!(
+            bad.verify(&vk, &messages[4]),
+            Err(MerkleError::InvalidProof)
+        );
+
+        // Malformed encodings
+        let bytes = proof.to_bytes();
+        assert_eq!(
+            MerkleProof::<P>::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(MerkleError::Encoding)
+        );
+        assert_eq!(
+            MerkleProof::<P>::from_bytes(&[]),
+            Err(MerkleError::Encoding)
+        );
+        let mut bytes = bytes;
+        bytes[0] = 2;
+        assert_eq!(
+            MerkleProof::<P>::from_bytes(&bytes),
+            Err(MerkleError::UnsupportedVersion(2))
+        );
+    }
+
+    test_fast_parameter_sets!(test_merkle_batch);
+    #[cfg(feature = "shake-128f")]
+    gen_test!(test_merkle_proof_tampering, SLH_DSA_SHAKE_128f);
+}
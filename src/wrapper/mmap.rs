@@ -0,0 +1,206 @@
+//! Signing and verifying files without reading them into memory.
+//!
+//! `slhdsa-c` takes the message as a single buffer. Memory-mapping the file
+//! avoids copying it into one: the operating system pages the file in (and
+//! out) as the C library reads it, so even disk images larger than the
+//! available memory can be signed.
+//!
+//! Sources that cannot be mapped, or that may be modified meanwhile, can be
+//! streamed instead with the `stream` feature, which hashes the message in
+//! Rust and signs its digest (see the `stream` module).
+//!
+//! The signatures are byte-identical to [`SigningKey::try_sign_with_ctx()`]
+//! over the content of the file:
+//!
+//! ```rust
+//! # use slhdsa_c_rs::*;
+//! # use SLH_DSA_SHAKE_128f as P;
+//! # let path = std::env::temp_dir().join("slhdsa-c-rs-mmap-doctest.bin");
+//! # std::fs::write(&path, b"Hello, world!").unwrap();
+//! let (sk, vk) = keygen::<P>().expect("Keygen failed");
+//!
+//! let file = std::fs::File::open(&path).unwrap();
+//! // SAFETY: nothing else modifies the file meanwhile
+//! let sig = unsafe { sk.try_sign_file_with_ctx(&file, b"") }.expect("Signing failed");
+//! assert_eq!(sig, sk.try_sign_with_ctx(b"Hello, world!", b"").unwrap());
+//! assert!(unsafe { vk.verify_file_with_ctx(&file, b"", &sig) }.is_ok());
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+//!
+//! # Safety
+//!
+//! The functions of this module are `unsafe`: the file must not be modified
+//! or truncated, by this process or another one, while it is mapped. The C
+//! library would then read memory changing under it, or crash with `SIGBUS`
+//! past the new end of the file. Use them on files nothing else writes to,
+//! such as release artifacts, or read the file into memory instead.
+
+use std::fs::File;
+
+use memmap2::Mmap;
+
+#[cfg(feature = "sign")]
+use super::SigningKey;
+use super::{ParameterSet, Signature, VerifyingKey};
+
+/// Map `file` read-only, and call `f` with its content
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+unsafe fn with_mapped<T>(
+    file: &File,
+    f: impl FnOnce(&[u8]) -> Result<T, signature::Error>,
+) -> Result<T, signature::Error> {
+    let len = file.metadata().map_err(|_| signature::Error::new())?.len();
+    // Empty mappings are rejected by some platforms
+    if len == 0 {
+        return f(&[]);
+    }
+    // SAFETY: the mapping is read-only and dropped before returning, and the
+    // caller guarantees that the file is not modified meanwhile
+    let map = unsafe { Mmap::map(file) }.map_err(|_| signature::Error::new())?;
+    f(&map)
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> SigningKey<P> {
+    /// Deterministically sign the content of `file` under `context`, see
+    /// [`Self::try_sign_with_ctx()`]
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if the file cannot be mapped, or if
+    /// signing fails.
+    ///
+    /// # Safety
+    ///
+    /// `file` must not be modified or truncated meanwhile, see the
+    /// [module documentation](self).
+    pub unsafe fn try_sign_file_with_ctx(
+        &self,
+        file: &File,
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        // SAFETY: guaranteed by the caller
+        unsafe { with_mapped(file, |message| self.try_sign_with_ctx(message, context)) }
+    }
+
+    /// Produce a hedged signature of the content of `file` under `context`,
+    /// see [`Self::try_sign_hedged_with_ctx()`]
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if the file cannot be mapped, or if
+    /// the entropy source or signing fails.
+    ///
+    /// # Safety
+    ///
+    /// `file` must not be modified or truncated meanwhile, see the
+    /// [module documentation](self).
+    pub unsafe fn try_sign_file_hedged_with_ctx(
+        &self,
+        file: &File,
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        // SAFETY: guaranteed by the caller
+        unsafe {
+            with_mapped(file, |message| {
+                self.try_sign_hedged_with_ctx(message, context)
+            })
+        }
+    }
+}
+
+impl<P: ParameterSet> VerifyingKey<P> {
+    /// Verify `signature` of the content of `file` under `context`, see
+    /// [`Self::verify_with_ctx()`]
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if the file cannot be mapped, or if
+    /// the signature is inauthentic.
+    ///
+    /// # Safety
+    ///
+    /// `file` must not be modified or truncated meanwhile, see the
+    /// [module documentation](self).
+    pub unsafe fn verify_file_with_ctx(
+        &self,
+        file: &File,
+        context: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), signature::Error> {
+        // SAFETY: guaranteed by the caller
+        unsafe {
+            with_mapped(file, |message| {
+                self.verify_with_ctx(message, context, signature)
+            })
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sign", slhdsa_fast_sets))]
+mod tests {
+    use super::*;
+    use crate::wrapper::utils::macros::{gen_test, test_fast_parameter_sets};
+    use crate::*;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::vec::Vec;
+
+    /// A temporary file, removed on drop
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, content: &[u8]) -> Self {
+            let path = std::env::temp_dir()
+                .join(std::format!("slhdsa-c-rs-{}-{name}", std::process::id()));
+            let mut file = File::create(&path).expect("Failed to create the file");
+            file.write_all(content).expect("Failed to write the file");
+            Self(path)
+        }
+
+        fn open(&self) -> File {
+            File::open(&self.0).expect("Failed to open the file")
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn test_sign_file<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let ctx = b"file";
+        let content: Vec<u8> = (0..100_000u32)
+            .map(|i| u8::try_from(i % 251).unwrap())
+            .collect();
+
+        for (name, content) in [("empty", &content[..0]), ("large", &content[..])] {
+            let tmp = TempFile::new(&std::format!("{}-{name}", P::NAME), content);
+            let file = tmp.open();
+
+            // SAFETY: the temporary file is only written by `TempFile::new()`
+            unsafe {
+                let sig = sk
+                    .try_sign_file_with_ctx(&file, ctx)
+                    .expect("Signing failed");
+                assert_eq!(sig, sk.try_sign_with_ctx(content, ctx).unwrap());
+                vk.verify_file_with_ctx(&file, ctx, &sig)
+                    .expect("Verification failed");
+                assert!(vk.verify_file_with_ctx(&file, b"", &sig).is_err());
+            }
+
+            // SAFETY: as above
+            let sig =
+                unsafe { sk.try_sign_file_hedged_with_ctx(&file, ctx) }.expect("Signing failed");
+            vk.verify_with_ctx(content, ctx, &sig)
+                .expect("Verification failed");
+        }
+    }
+
+    test_fast_parameter_sets!(test_sign_file);
+}
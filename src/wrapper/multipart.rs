@@ -0,0 +1,199 @@
+//! Signing and verifying messages split across several buffers.
+//!
+//! With the `stream` feature, the parts are hashed in turn, as the message
+//! of the `stream` module, and never joined. Otherwise, `slhdsa-c` takes
+//! the message as a single buffer: a message made of several parts is
+//! joined into one allocation first, unless it has a single (non-empty)
+//! part. Signing or verifying then costs an extra allocation and copy of
+//! the whole message. This module is therefore available with either
+//! `stream` or `alloc`.
+
+#[cfg(not(all(feature = "stream", not(feature = "system"))))]
+use alloc::borrow::Cow;
+
+#[cfg(feature = "sign")]
+use super::SigningKey;
+use super::{ParameterSet, Signature, VerifyingKey, EMPTY_CTX};
+
+pub use signature::{MultipartSigner, MultipartVerifier};
+
+/// The message made of `parts`, borrowed when possible
+#[cfg(not(all(feature = "stream", not(feature = "system"))))]
+fn join<'a>(parts: &[&'a [u8]]) -> Cow<'a, [u8]> {
+    let mut non_empty = parts.iter().filter(|p| !p.is_empty());
+    match (non_empty.next(), non_empty.next()) {
+        (None, _) => Cow::Borrowed(&[]),
+        (Some(part), None) => Cow::Borrowed(part),
+        _ => Cow::Owned(parts.concat()),
+    }
+}
+
+/// Sign the message made of `parts` under `context`, deterministically if
+/// `addrnd` is `None`
+#[cfg(feature = "sign")]
+fn sign_parts<P: ParameterSet>(
+    sk: &SigningKey<P>,
+    parts: &[&[u8]],
+    context: &[u8],
+    addrnd: Option<&[u8]>,
+) -> Result<Signature<P>, signature::Error> {
+    #[cfg(all(feature = "stream", not(feature = "system")))]
+    return sk.sign_streamed(context, addrnd, &mut |update| {
+        for part in parts {
+            update(part);
+        }
+        Ok(())
+    });
+    #[cfg(not(all(feature = "stream", not(feature = "system"))))]
+    sk.sign_inner(&join(parts), context, addrnd)
+}
+
+/// Verify `signature` of the message made of `parts` under `context`
+fn verify_parts<P: ParameterSet>(
+    vk: &VerifyingKey<P>,
+    parts: &[&[u8]],
+    context: &[u8],
+    signature: &Signature<P>,
+) -> Result<(), signature::Error> {
+    #[cfg(all(feature = "stream", not(feature = "system")))]
+    return vk.verify_streamed(context, signature, &mut |update| {
+        for part in parts {
+            update(part);
+        }
+        Ok(())
+    });
+    #[cfg(not(all(feature = "stream", not(feature = "system"))))]
+    vk.verify_with_ctx(&join(parts), context, signature)
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> SigningKey<P> {
+    /// Deterministically sign the message made of `parts` under `context`,
+    /// see [`Self::try_sign_with_ctx()`]
+    ///
+    /// Without the `stream` feature, the parts are joined into one
+    /// allocation first, at the cost of a copy of the whole message.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`signature::Error`] if the underlying FFI
+    /// signature generation fails.
+    pub fn try_multipart_sign_with_ctx(
+        &self,
+        parts: &[&[u8]],
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        sign_parts(self, parts, context, None)
+    }
+
+    /// Produce a hedged signature of the message made of `parts` under
+    /// `context`, see [`Self::try_sign_hedged_with_ctx()`]
+    ///
+    /// Without the `stream` feature, the parts are joined into one
+    /// allocation first, at the cost of a copy of the whole message.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`signature::Error`] if the entropy source
+    /// fails, or if the underlying FFI signature generation fails.
+    pub fn try_multipart_sign_hedged_with_ctx(
+        &self,
+        parts: &[&[u8]],
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        Self::with_fresh_addrnd(|addrnd| sign_parts(self, parts, context, Some(addrnd)))
+    }
+}
+
+/// Without the `stream` feature, the parts are joined into one allocation
+/// first, see [`SigningKey::try_multipart_sign_with_ctx()`].
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> MultipartSigner<Signature<P>> for SigningKey<P> {
+    fn try_multipart_sign(&self, msg: &[&[u8]]) -> Result<Signature<P>, signature::Error> {
+        self.try_multipart_sign_with_ctx(msg, EMPTY_CTX)
+    }
+}
+
+impl<P: ParameterSet> VerifyingKey<P> {
+    /// Verify `signature` of the message made of `parts` under `context`,
+    /// see [`Self::verify_with_ctx()`]
+    ///
+    /// Without the `stream` feature, the parts are joined into one
+    /// allocation first, at the cost of a copy of the whole message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`signature::Error`] if it is inauthentic.
+    pub fn multipart_verify_with_ctx(
+        &self,
+        parts: &[&[u8]],
+        context: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), signature::Error> {
+        verify_parts(self, parts, context, signature)
+    }
+}
+
+/// Without the `stream` feature, the parts are joined into one allocation
+/// first, see [`VerifyingKey::multipart_verify_with_ctx()`].
+impl<P: ParameterSet> MultipartVerifier<Signature<P>> for VerifyingKey<P> {
+    fn multipart_verify(
+        &self,
+        msg: &[&[u8]],
+        signature: &Signature<P>,
+    ) -> Result<(), signature::Error> {
+        self.multipart_verify_with_ctx(msg, EMPTY_CTX, signature)
+    }
+}
+
+#[cfg(all(test, feature = "sign", slhdsa_fast_sets))]
+mod tests {
+    use super::*;
+    use crate::wrapper::utils::macros::{gen_test, test_fast_parameter_sets};
+    use crate::*;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    #[cfg(not(all(feature = "stream", not(feature = "system"))))]
+    #[test]
+    fn test_join() {
+        assert!(matches!(join(&[]), Cow::Borrowed([])));
+        assert!(matches!(join(&[b"", b"abc", b""]), Cow::Borrowed(b"abc")));
+        let joined = join(&[b"ab", b"", b"c"]);
+        assert!(matches!(joined, Cow::Owned(_)));
+        assert_eq!(&*joined, b"abc");
+    }
+
+    fn test_multipart<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let (header, body, trailer): (&[u8], &[u8], &[u8]) = (b"header", b"body", b"trailer");
+        let parts = [header, body, trailer];
+        let msg: Vec<u8> = parts.concat();
+        let ctx = b"Test context";
+
+        let sig = sk.multipart_sign(&parts);
+        assert_eq!(sig, sk.sign(&msg));
+        vk.multipart_verify(&parts, &sig)
+            .expect("multipart_verify() should not fail on the happy path");
+        vk.multipart_verify(&[&msg[..3], &msg[3..]], &sig)
+            .expect("The split of the message should not matter");
+        assert!(vk.multipart_verify(&[header, trailer, body], &sig).is_err());
+
+        let sig = sk
+            .try_multipart_sign_with_ctx(&parts, ctx)
+            .expect("Signing failed");
+        assert_eq!(sig, sk.try_sign_with_ctx(&msg, ctx).unwrap());
+        vk.multipart_verify_with_ctx(&parts, ctx, &sig)
+            .expect("Verification failed");
+        assert!(vk.multipart_verify(&parts, &sig).is_err());
+
+        let sig = sk
+            .try_multipart_sign_hedged_with_ctx(&parts, ctx)
+            .expect("Signing failed");
+        vk.verify_with_ctx(&msg, ctx, &sig)
+            .expect("Verification failed");
+    }
+
+    test_fast_parameter_sets!(test_multipart);
+}
@@ -0,0 +1,401 @@
+//! Signing and verifying from async code.
+//!
+//! SLH-DSA operations are CPU-bound and slow: signing with the "s"
+//! parameter sets takes up to a second, and would stall every other task
+//! of an async runtime thread. With the `async` feature, the `*_async`
+//! methods of [`SigningKey`], and [`VerifyingKey`] through [`AsyncVerifier`],
+//! run the C library on the blocking thread pool of [Tokio](tokio) instead.
+//!
+//! [`SigningKey`] also implements [`signature::AsyncSigner`], through its
+//! blanket implementation for every [`signature::Signer`]: this one signs on
+//! the calling thread, and does block the runtime. Wrap the key in a
+//! [`BlockingPoolSigner`] to get an [`signature::AsyncSigner`] that signs on
+//! the blocking pool, e.g. for code that is generic over the signer.
+//!
+//! At most [`concurrency_limit()`] operations run at once, across the whole
+//! process, so that a burst of requests does not fill the blocking pool
+//! (which is shared with file system operations, among others). The other
+//! ones wait for their turn without blocking.
+//!
+//! ```rust
+//! use slhdsa_c_rs::nonblocking::AsyncVerifier;
+//! use slhdsa_c_rs::*;
+//! use SLH_DSA_SHAKE_128f as P;
+//!
+//! let rt = tokio::runtime::Builder::new_current_thread()
+//!     .build()
+//!     .unwrap();
+//! rt.block_on(async {
+//!     let (sk, vk) = keygen::<P>().expect("Keygen failed");
+//!     let msg = b"Hello, world!";
+//!
+//!     let sig = sk.sign_with_ctx_async(msg, b"").await.expect("Signing failed");
+//!     assert!(vk.verify_async(msg, &sig).await.is_ok());
+//! });
+//! ```
+//!
+//! The key, message and context are copied to the blocking pool, since the
+//! operation keeps running there if its future is dropped.
+
+use core::fmt;
+use core::future::Future;
+use core::num::NonZeroUsize;
+use std::sync::OnceLock;
+
+use tokio::sync::Semaphore;
+
+#[cfg(feature = "sign")]
+use super::SigningKey;
+use super::{ParameterSet, Signature, VerifyingKey, EMPTY_CTX};
+
+/// Asynchronously verify signatures, like [`signature::Verifier`]
+pub trait AsyncVerifier<S> {
+    /// Verify the signature `signature` of `msg`
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if the signature is inauthentic, or if
+    /// the verification task fails.
+    fn verify_async(
+        &self,
+        msg: &[u8],
+        signature: &S,
+    ) -> impl Future<Output = Result<(), signature::Error>> + Send;
+}
+
+/// Error returned by [`set_concurrency_limit()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyLimitError {
+    /// The limit was already set, or an operation already used the default
+    /// one
+    AlreadySet,
+}
+
+impl fmt::Display for ConcurrencyLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcurrencyLimitError::AlreadySet => {
+                write!(f, "The concurrency limit is already set")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ConcurrencyLimitError {}
+
+struct Limiter {
+    limit: usize,
+    semaphore: Semaphore,
+}
+
+static LIMITER: OnceLock<Limiter> = OnceLock::new();
+
+impl Limiter {
+    fn new(limit: NonZeroUsize) -> Self {
+        Self {
+            limit: limit.get(),
+            semaphore: Semaphore::new(limit.get()),
+        }
+    }
+
+    fn get() -> &'static Self {
+        LIMITER.get_or_init(|| {
+            let limit = std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+            Self::new(limit)
+        })
+    }
+}
+
+/// Set the maximum number of operations running at once on the blocking
+/// pool.
+///
+/// This must be called before the first async operation: the limit
+/// defaults to the number of available CPUs.
+///
+/// # Errors
+///
+/// Returns [`ConcurrencyLimitError::AlreadySet`] if the limit is already
+/// set.
+pub fn set_concurrency_limit(limit: NonZeroUsize) -> Result<(), ConcurrencyLimitError> {
+    LIMITER
+        .set(Limiter::new(limit))
+        .map_err(|_| ConcurrencyLimitError::AlreadySet)
+}
+
+/// The maximum number of operations running at once on the blocking pool
+pub fn concurrency_limit() -> usize {
+    Limiter::get().limit
+}
+
+/// Run `f` on the blocking pool, once the concurrency limit allows it
+async fn run_blocking<T, F>(f: F) -> Result<T, signature::Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let permit = Limiter::get()
+        .semaphore
+        .acquire()
+        .await
+        .map_err(|_| signature::Error::new())?;
+    tokio::task::spawn_blocking(move || {
+        // Released once done, even if the caller stopped waiting
+        let _permit = permit;
+        f()
+    })
+    .await
+    .map_err(|_| signature::Error::new())
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet + 'static> SigningKey<P> {
+    /// Sign `message` under `context` on the blocking pool, see
+    /// [`Self::try_sign_with_ctx()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if signing fails, or if the signing
+    /// task fails.
+    pub async fn sign_with_ctx_async(
+        &self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        let sk = self.clone();
+        let (message, context) = (message.to_vec(), context.to_vec());
+        run_blocking(move || sk.try_sign_with_ctx(&message, &context)).await?
+    }
+
+    /// Produce a hedged signature of `message` under `context` on the
+    /// blocking pool, see [`Self::try_sign_hedged_with_ctx()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if the entropy source or signing
+    /// fails, or if the signing task fails.
+    pub async fn sign_hedged_with_ctx_async(
+        &self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        let sk = self.clone();
+        let (message, context) = (message.to_vec(), context.to_vec());
+        run_blocking(move || sk.try_sign_hedged_with_ctx(&message, &context)).await?
+    }
+}
+
+/// A [`SigningKey`] whose [`signature::AsyncSigner`] implementation signs on
+/// the blocking pool, see [`SigningKey::sign_with_ctx_async()`].
+///
+/// ```rust
+/// use signature::AsyncSigner;
+/// use slhdsa_c_rs::nonblocking::BlockingPoolSigner;
+/// use slhdsa_c_rs::*;
+/// use SLH_DSA_SHAKE_128f as P;
+///
+/// let rt = tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap();
+/// rt.block_on(async {
+///     let signer = BlockingPoolSigner::new(SigningKey::<P>::new().expect("Keygen failed"));
+///     let msg = b"Hello, world!";
+///
+///     let sig: Signature<P> = signer.sign_async(msg).await.expect("Signing failed");
+///     assert!(signer.verifying_key().verify(msg, &sig).is_ok());
+/// });
+/// ```
+#[cfg(feature = "sign")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockingPoolSigner<P: ParameterSet>(SigningKey<P>);
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> BlockingPoolSigner<P> {
+    /// Wrap `sk`
+    pub fn new(sk: SigningKey<P>) -> Self {
+        Self(sk)
+    }
+
+    /// Unwrap the signing key
+    pub fn into_inner(self) -> SigningKey<P> {
+        self.0
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> From<SigningKey<P>> for BlockingPoolSigner<P> {
+    fn from(sk: SigningKey<P>) -> Self {
+        Self(sk)
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> AsRef<SigningKey<P>> for BlockingPoolSigner<P> {
+    fn as_ref(&self) -> &SigningKey<P> {
+        &self.0
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> signature::Keypair for BlockingPoolSigner<P> {
+    type VerifyingKey = VerifyingKey<P>;
+
+    fn verifying_key(&self) -> VerifyingKey<P> {
+        self.0.verifying_key()
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet + 'static> signature::AsyncSigner<Signature<P>> for BlockingPoolSigner<P> {
+    async fn sign_async(&self, msg: &[u8]) -> Result<Signature<P>, signature::Error> {
+        self.0.sign_with_ctx_async(msg, EMPTY_CTX).await
+    }
+}
+
+impl<P: ParameterSet + 'static> VerifyingKey<P> {
+    /// Verify `signature` of `message` under `context` on the blocking
+    /// pool, see [`Self::verify_with_ctx()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if the signature is inauthentic, or if
+    /// the verification task fails.
+    pub async fn verify_with_ctx_async(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), signature::Error> {
+        let vk = self.clone();
+        // Boxed, not to make the future as large as the signature
+        let signature = std::boxed::Box::new(signature.clone());
+        let (message, context) = (message.to_vec(), context.to_vec());
+        run_blocking(move || vk.verify_with_ctx(&message, &context, &signature)).await?
+    }
+}
+
+impl<P: ParameterSet + 'static> AsyncVerifier<Signature<P>> for VerifyingKey<P> {
+    fn verify_async(
+        &self,
+        msg: &[u8],
+        signature: &Signature<P>,
+    ) -> impl Future<Output = Result<(), signature::Error>> + Send {
+        self.verify_with_ctx_async(msg, EMPTY_CTX, signature)
+    }
+}
+
+#[cfg(all(test, feature = "sign", feature = "shake-128f"))]
+mod tests {
+    use super::*;
+    use crate::*;
+    use std::vec::Vec;
+
+    #[tokio::test]
+    async fn test_sign_verify_async() {
+        use SLH_DSA_SHAKE_128f as P;
+
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let msg = b"Hello, world!";
+        let ctx = b"Test context";
+
+        let sig = sk
+            .sign_with_ctx_async(msg, EMPTY_CTX)
+            .await
+            .expect("Signing failed");
+        assert_eq!(sig, sk.sign(msg));
+        vk.verify_async(msg, &sig)
+            .await
+            .expect("Verification failed");
+        assert!(vk.verify_async(b"Goodbye", &sig).await.is_err());
+
+        let sig = sk
+            .sign_hedged_with_ctx_async(msg, ctx)
+            .await
+            .expect("Signing failed");
+        vk.verify_with_ctx_async(msg, ctx, &sig)
+            .await
+            .expect("Verification failed");
+        assert!(vk.verify_async(msg, &sig).await.is_err());
+    }
+
+    /// The runtime thread keeps running other tasks while signing
+    #[tokio::test]
+    async fn test_runtime_not_blocked() {
+        let sk = SigningKey::<SLH_DSA_SHAKE_128f>::new().expect("Keygen failed");
+        let mut ticks = 0usize;
+        let ticker = async {
+            loop {
+                tokio::task::yield_now().await;
+                ticks += 1;
+            }
+        };
+
+        tokio::select! {
+            biased;
+            sig = sk.sign_with_ctx_async(b"Hello, world!", EMPTY_CTX) => {
+                sig.expect("Signing failed");
+            }
+            () = ticker => unreachable!(),
+        }
+        assert!(ticks > 0);
+    }
+
+    /// The runtime thread keeps running other tasks while signing through
+    /// the generic [`signature::AsyncSigner`]
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_blocking_pool_signer() {
+        use signature::AsyncSigner;
+
+        async fn sign<S: AsyncSigner<Signature<SLH_DSA_SHAKE_128f>>>(
+            signer: &S,
+            msg: &[u8],
+        ) -> Signature<SLH_DSA_SHAKE_128f> {
+            signer.sign_async(msg).await.expect("Signing failed")
+        }
+
+        let sk = SigningKey::<SLH_DSA_SHAKE_128f>::new().expect("Keygen failed");
+        let signer = BlockingPoolSigner::new(sk.clone());
+        let msg = b"Hello, world!";
+        let mut ticks = 0usize;
+        let ticker = async {
+            loop {
+                tokio::task::yield_now().await;
+                ticks += 1;
+            }
+        };
+
+        tokio::select! {
+            biased;
+            sig = sign(&signer, msg) => {
+                assert_eq!(sig, sk.sign(msg));
+                assert!(signer.verifying_key().verify(msg, &sig).is_ok());
+            }
+            () = ticker => unreachable!(),
+        }
+        assert!(ticks > 0);
+        assert_eq!(signer.into_inner(), sk);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let sk = SigningKey::<SLH_DSA_SHAKE_128f>::new().expect("Keygen failed");
+        let messages: Vec<[u8; 1]> = (0u8..8).map(|i| [i]).collect();
+
+        // More operations than the limit: the others wait for their turn
+        let limit = concurrency_limit();
+        assert!(limit >= 1);
+        let signatures = futures_util::future::join_all(
+            messages
+                .iter()
+                .map(|m| sk.sign_with_ctx_async(m, EMPTY_CTX)),
+        );
+        for (m, sig) in messages.iter().zip(signatures.await) {
+            assert_eq!(sig.expect("Signing failed"), sk.sign(m));
+        }
+        assert_eq!(Limiter::get().semaphore.available_permits(), limit);
+
+        assert_eq!(
+            set_concurrency_limit(NonZeroUsize::MIN),
+            Err(ConcurrencyLimitError::AlreadySet)
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Keys prepared for many operations under the same key.
+//!
+//! `slhdsa-c` derives the per-key hash state (for the SHA2 parameter sets,
+//! the SHA-256, and for 192/256-bit security SHA-512, midstates of
+//! `PK.seed`) at the start of every operation. [`PreparedVerifyingKey`] and
+//! [`PreparedSigningKey`] derive it once, when they are created, and restore
+//! it in later operations (see `csrc/prepared_key.c`).
+//!
+//! The state is captured by running one operation when the key is prepared:
+//! a verification of an all-zero signature, or a signature of the empty
+//! message. If it cannot be captured, the prepared key falls back to the
+//! plain one, so that the results never differ:
+//!
+//! ```rust
+//! # use slhdsa_c_rs::*;
+//! # use SLH_DSA_SHA2_128f as P;
+//! let (sk, vk) = keygen::<P>().expect("Keygen failed");
+//! let msg = b"Hello, world!";
+//!
+//! let prepared_sk = PreparedSigningKey::new(sk.clone());
+//! let sig = prepared_sk.try_sign_with_ctx(msg, b"").expect("Signing failed");
+//! assert_eq!(sig, sk.try_sign_with_ctx(msg, b"").unwrap());
+//!
+//! let prepared_vk = PreparedVerifyingKey::new(vk);
+//! assert!(prepared_vk.verify_with_ctx(msg, b"", &sig).is_ok());
+//! ```
+//!
+//! The gain is bounded by the cost of the derivation, one or two
+//! compression function calls per operation, against thousands of hash
+//! calls for the operation itself: see `benches/prepared_key.rs`.
+//!
+//! Like custom parameter sets, this relies on the private headers of
+//! `slhdsa-c`, and is not available with the `system` feature.
+
+use alloc::boxed::Box;
+use alloc::vec;
+
+use generic_array::GenericArray;
+
+#[cfg(feature = "sign")]
+use super::SigningKey;
+use super::{ParameterSet, Signature, VerifyingKey, EMPTY_CTX};
+use crate::ffi::{self, c_int};
+use crate::transcoding::AsBytes;
+
+unsafe extern "C" {
+    fn slhdsa_rs_prepared_size() -> usize;
+
+    fn slhdsa_rs_prepare(buf: *mut u128, base: *const ffi::slh_param_t) -> *const ffi::slh_param_t;
+
+    fn slhdsa_rs_is_prepared(buf: *const u128) -> c_int;
+}
+
+/// Copy of a parameter set restoring the captured state of one key
+struct PreparedState {
+    // 16-byte aligned, and never moved once `prm` points into it
+    buf: Box<[u128]>,
+    prm: *const ffi::slh_param_t,
+}
+
+// SAFETY: The state is only read once captured.
+unsafe impl Send for PreparedState {}
+unsafe impl Sync for PreparedState {}
+
+impl PreparedState {
+    /// Capture the state during `op`, which must call the C library with the
+    /// parameter set it is given.
+    fn capture<P: ParameterSet>(op: impl FnOnce(*const ffi::slh_param_t)) -> Option<Self> {
+        let len = unsafe { slhdsa_rs_prepared_size() }.div_ceil(size_of::<u128>());
+        let mut buf = vec![0u128; len].into_boxed_slice();
+        let prm = unsafe { slhdsa_rs_prepare(buf.as_mut_ptr(), P::prm_as_ptr()) };
+
+        op(prm);
+
+        (unsafe { slhdsa_rs_is_prepared(buf.as_ptr()) } == 1).then_some(Self { buf, prm })
+    }
+}
+
+impl Drop for PreparedState {
+    fn drop(&mut self) {
+        // The state of a signing key holds its secret seeds
+        for word in &mut self.buf {
+            // SAFETY: `word` is a valid `&mut u128`.
+            unsafe { core::ptr::write_volatile(word, 0) };
+        }
+    }
+}
+
+/// A [`VerifyingKey`] prepared for verifying many signatures.
+pub struct PreparedVerifyingKey<P: ParameterSet> {
+    vk: VerifyingKey<P>,
+    state: Option<PreparedState>,
+}
+
+impl<P: ParameterSet> PreparedVerifyingKey<P> {
+    /// Prepare `vk`, at the cost of about one verification.
+    #[must_use]
+    pub fn new(vk: VerifyingKey<P>) -> Self {
+        let sig = Signature::<P> {
+            sig: GenericArray::default(),
+        };
+        let state = PreparedState::capture::<P>(|prm| {
+            let _ = vk.verify_with(|pk, _| unsafe {
+                ffi::slh_verify(
+                    EMPTY_CTX.as_ptr(),
+                    0,
+                    sig.as_bytes().as_ptr(),
+                    sig.as_bytes().len(),
+                    EMPTY_CTX.as_ptr(),
+                    0,
+                    pk,
+                    prm,
+                )
+            });
+        });
+        Self { vk, state }
+    }
+
+    /// The key this was prepared from
+    pub fn verifying_key(&self) -> &VerifyingKey<P> {
+        &self.vk
+    }
+
+    /// Whether the state of the key was captured. Otherwise, operations run
+    /// as with the plain key.
+    pub fn is_prepared(&self) -> bool {
+        self.state.is_some()
+    }
+
+    /// Same as [`VerifyingKey::verify_with_ctx()`]
+    ///
+    /// # Errors
+    /// Returns an error if the signature is invalid.
+    pub fn verify_with_ctx(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), signature::Error> {
+        let Some(state) = &self.state else {
+            return self.vk.verify_with_ctx(message, context, signature);
+        };
+        let sig = signature.as_bytes();
+        self.vk.verify_with(|pk, _| unsafe {
+            ffi::slh_verify(
+                message.as_ptr(),
+                message.len(),
+                sig.as_ptr(),
+                sig.len(),
+                context.as_ptr(),
+                context.len(),
+                pk,
+                state.prm,
+            )
+        })
+    }
+}
+
+impl<P: ParameterSet> From<VerifyingKey<P>> for PreparedVerifyingKey<P> {
+    fn from(vk: VerifyingKey<P>) -> Self {
+        Self::new(vk)
+    }
+}
+
+impl<P: ParameterSet> signature::Verifier<Signature<P>> for PreparedVerifyingKey<P> {
+    fn verify(&self, msg: &[u8], signature: &Signature<P>) -> Result<(), signature::Error> {
+        self.verify_with_ctx(msg, EMPTY_CTX, signature)
+    }
+}
+
+/// A [`SigningKey`] prepared for producing many signatures.
+#[cfg(feature = "sign")]
+pub struct PreparedSigningKey<P: ParameterSet> {
+    sk: SigningKey<P>,
+    state: Option<PreparedState>,
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> PreparedSigningKey<P> {
+    /// Prepare `sk`, at the cost of one signature.
+    #[must_use]
+    pub fn new(sk: SigningKey<P>) -> Self {
+        let state = PreparedState::capture::<P>(|prm| {
+            let _ = Self::sign_prepared(&sk, prm, EMPTY_CTX, EMPTY_CTX, None);
+        });
+        Self { sk, state }
+    }
+
+    /// The key this was prepared from
+    pub fn signing_key(&self) -> &SigningKey<P> {
+        &self.sk
+    }
+
+    /// Whether the state of the key was captured. Otherwise, operations run
+    /// as with the plain key.
+    pub fn is_prepared(&self) -> bool {
+        self.state.is_some()
+    }
+
+    /// Same as [`SigningKey::try_sign_with_ctx()`]
+    ///
+    /// # Errors
+    /// Returns an error if the context is too long, or signing fails.
+    pub fn try_sign_with_ctx(
+        &self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        self.sign_inner(message, context, None)
+    }
+
+    /// Same as [`SigningKey::try_sign_hedged_with_ctx()`]
+    ///
+    /// # Errors
+    /// Returns an error if the context is too long, the entropy source
+    /// fails, or signing fails.
+    pub fn try_sign_hedged_with_ctx(
+        &self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        SigningKey::<P>::with_fresh_addrnd(|addrnd| self.sign_inner(message, context, Some(addrnd)))
+    }
+
+    fn sign_inner(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        addrnd: Option<&[u8]>,
+    ) -> Result<Signature<P>, signature::Error> {
+        match &self.state {
+            Some(state) => Self::sign_prepared(&self.sk, state.prm, message, context, addrnd),
+            None => self.sk.sign_inner(message, context, addrnd),
+        }
+    }
+
+    fn sign_prepared(
+        sk: &SigningKey<P>,
+        prm: *const ffi::slh_param_t,
+        message: &[u8],
+        context: &[u8],
+        addrnd: Option<&[u8]>,
+    ) -> Result<Signature<P>, signature::Error> {
+        sk.sign_with(addrnd, |sig, sk, addrnd, _| unsafe {
+            ffi::slh_sign(
+                sig,
+                message.as_ptr(),
+                message.len(),
+                context.as_ptr(),
+                context.len(),
+                sk,
+                addrnd,
+                prm,
+            )
+        })
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> From<SigningKey<P>> for PreparedSigningKey<P> {
+    fn from(sk: SigningKey<P>) -> Self {
+        Self::new(sk)
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> signature::Signer<Signature<P>> for PreparedSigningKey<P> {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature<P>, signature::Error> {
+        self.try_sign_with_ctx(msg, EMPTY_CTX)
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> signature::Keypair for PreparedSigningKey<P> {
+    type VerifyingKey = VerifyingKey<P>;
+
+    fn verifying_key(&self) -> Self::VerifyingKey {
+        self.sk.verifying_key()
+    }
+}
+
+#[cfg(all(test, feature = "sign", slhdsa_fast_sets))]
+mod tests {
+    use super::*;
+    use crate::wrapper::utils::macros::{gen_test, test_fast_parameter_sets};
+    use crate::*;
+
+    fn test_prepared_keys<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().unwrap();
+        let msg = b"prepared key";
+
+        let prepared_sk = PreparedSigningKey::new(sk.clone());
+        assert!(prepared_sk.is_prepared());
+        let sig = prepared_sk.try_sign_with_ctx(msg, b"ctx").unwrap();
+        assert_eq!(sig, sk.try_sign_with_ctx(msg, b"ctx").unwrap());
+        let sig = prepared_sk.try_sign_hedged_with_ctx(msg, b"ctx").unwrap();
+        vk.verify_with_ctx(msg, b"ctx", &sig).unwrap();
+
+        let prepared_vk = PreparedVerifyingKey::new(vk);
+        assert!(prepared_vk.is_prepared());
+        prepared_vk.verify_with_ctx(msg, b"ctx", &sig).unwrap();
+        assert!(prepared_vk.verify_with_ctx(msg, b"", &sig).is_err());
+        assert!(prepared_vk.verify_with_ctx(b"other", b"ctx", &sig).is_err());
+
+        let (_, other_vk) = keygen::<P>().unwrap();
+        let other_vk = PreparedVerifyingKey::new(other_vk);
+        assert!(other_vk.verify_with_ctx(msg, b"ctx", &sig).is_err());
+    }
+
+    test_fast_parameter_sets!(test_prepared_keys);
+}
@@ -0,0 +1,208 @@
+//! Property-based tests for the wrapper API, generated for every parameter
+//! set.
+//!
+//! Signing with the "small" (`s`) parameter sets is slow, so each property
+//! only runs a handful of cases by default: set `PROPTEST_CASES` to run more.
+
+extern crate std;
+
+use std::vec::Vec;
+
+use proptest::prelude::*;
+use proptest::sample::Index;
+use proptest::test_runner::{Config, TestRunner};
+
+use super::*;
+use utils::macros::{gen_test, test_parameter_sets};
+
+const DEFAULT_CASES: u32 = 4;
+const MAX_CTX_LEN: usize = 255;
+
+fn runner() -> TestRunner {
+    let cases = std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .unwrap_or(DEFAULT_CASES);
+    TestRunner::new(Config {
+        cases,
+        failure_persistence: None,
+        ..Config::default()
+    })
+}
+
+fn msg() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..1024)
+}
+
+fn ctx() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..=MAX_CTX_LEN)
+}
+
+/// Signatures verify, and keys and signatures survive encoding round-trips
+fn prop_sign_verify_roundtrip<P: ParameterSet>() {
+    let (sk, vk) = keygen::<P>().expect("Keygen failed");
+
+    runner()
+        .run(&(msg(), ctx()), |(msg, ctx)| {
+            let sig = sk.try_sign_with_ctx(&msg, &ctx).expect("Signing failed");
+            prop_assert!(vk.verify_with_ctx(&msg, &ctx, &sig).is_ok());
+
+            let decoded = Signature::<P>::try_from(sig.as_bytes()).expect("Decoding failed");
+            prop_assert_eq!(&decoded, &sig);
+            prop_assert!(vk.verify_with_ctx(&msg, &ctx, &decoded).is_ok());
+
+            let decoded = SigningKey::<P>::try_from(sk.as_bytes()).expect("Decoding failed");
+            prop_assert!(decoded == sk);
+            let decoded = VerifyingKey::<P>::try_from(vk.as_bytes()).expect("Decoding failed");
+            prop_assert!(decoded == vk);
+            Ok(())
+        })
+        .unwrap();
+}
+test_parameter_sets!(prop_sign_verify_roundtrip);
+
+/// Encodings of the wrong length are rejected
+fn prop_decode_wrong_length<P: ParameterSet>() {
+    let bytes = prop::collection::vec(any::<u8>(), 0..P::SIGNATURE_LEN + 64);
+
+    runner()
+        .run(&bytes, |bytes| {
+            let len = bytes.len();
+            prop_assert_eq!(
+                SigningKey::<P>::try_from(&bytes[..]).is_ok(),
+                len == P::SIGNING_KEY_LEN
+            );
+            prop_assert_eq!(
+                VerifyingKey::<P>::try_from(&bytes[..]).is_ok(),
+                len == P::VERIFYING_KEY_LEN
+            );
+            prop_assert_eq!(
+                Signature::<P>::try_from(&bytes[..]).is_ok(),
+                len == P::SIGNATURE_LEN
+            );
+            Ok(())
+        })
+        .unwrap();
+}
+test_parameter_sets!(prop_decode_wrong_length);
+
+/// Signatures do not verify for a modified message
+fn prop_modified_message<P: ParameterSet>() {
+    let (sk, vk) = keygen::<P>().expect("Keygen failed");
+    let msg = prop::collection::vec(any::<u8>(), 1..1024);
+
+    runner()
+        .run(&(msg, any::<Index>(), 1..=u8::MAX), |(msg, i, delta)| {
+            let sig = sk
+                .try_sign_with_ctx(&msg, EMPTY_CTX)
+                .expect("Signing failed");
+
+            let mut modified = msg.clone();
+            let i = i.index(modified.len());
+            modified[i] = modified[i].wrapping_add(delta);
+            prop_assert!(vk.verify(&modified, &sig).is_err());
+
+            let mut extended = msg;
+            extended.push(delta);
+            prop_assert!(vk.verify(&extended, &sig).is_err());
+            Ok(())
+        })
+        .unwrap();
+}
+test_parameter_sets!(prop_modified_message);
+
+/// Signatures do not verify under a different verifying key
+fn prop_wrong_key<P: ParameterSet>() {
+    let sk = SigningKey::<P>::new().expect("Keygen failed");
+    let wrong_vk = SigningKey::<P>::new()
+        .expect("Keygen failed")
+        .verifying_key();
+
+    runner()
+        .run(&(msg(), ctx()), |(msg, ctx)| {
+            let sig = sk.try_sign_with_ctx(&msg, &ctx).expect("Signing failed");
+            prop_assert!(sk.verifying_key().verify_with_ctx(&msg, &ctx, &sig).is_ok());
+            prop_assert!(wrong_vk.verify_with_ctx(&msg, &ctx, &sig).is_err());
+            Ok(())
+        })
+        .unwrap();
+}
+test_parameter_sets!(prop_wrong_key);
+
+/// Signatures with a flipped bit do not verify
+fn prop_modified_signature<P: ParameterSet>() {
+    let (sk, vk) = keygen::<P>().expect("Keygen failed");
+
+    runner()
+        .run(&(msg(), any::<Index>(), 0..8u8), |(msg, i, bit)| {
+            let sig = sk
+                .try_sign_with_ctx(&msg, EMPTY_CTX)
+                .expect("Signing failed");
+
+            let mut bytes = sig.as_bytes().to_vec();
+            let i = i.index(bytes.len());
+            bytes[i] ^= 1 << bit;
+            let modified = Signature::<P>::try_from(&bytes[..]).expect("Decoding failed");
+
+            prop_assert!(
+                vk.verify(&msg, &modified).is_err(),
+                "Verification should fail with a modified signature (byte {})",
+                i
+            );
+            Ok(())
+        })
+        .unwrap();
+}
+test_parameter_sets!(prop_modified_signature);
+
+/// Signatures only verify under the context they were created with, and
+/// contexts longer than 255 bytes are rejected
+fn prop_context_separation<P: ParameterSet>() {
+    let (sk, vk) = keygen::<P>().expect("Keygen failed");
+    let long_ctx = prop::collection::vec(any::<u8>(), MAX_CTX_LEN + 1..2 * MAX_CTX_LEN);
+
+    runner()
+        .run(
+            &(msg(), ctx(), ctx(), long_ctx),
+            |(msg, ctx, other_ctx, long_ctx)| {
+                prop_assume!(ctx != other_ctx);
+
+                let sig = sk.try_sign_with_ctx(&msg, &ctx).expect("Signing failed");
+                prop_assert!(vk.verify_with_ctx(&msg, &ctx, &sig).is_ok());
+                prop_assert!(vk.verify_with_ctx(&msg, &other_ctx, &sig).is_err());
+
+                prop_assert!(sk.try_sign_with_ctx(&msg, &long_ctx).is_err());
+                prop_assert!(vk.verify_with_ctx(&msg, &long_ctx, &sig).is_err());
+                Ok(())
+            },
+        )
+        .unwrap();
+}
+test_parameter_sets!(prop_context_separation);
+
+/// Deterministic signatures are equal, hedged signatures are not
+fn prop_hedged_vs_deterministic<P: ParameterSet>() {
+    let (sk, vk) = keygen::<P>().expect("Keygen failed");
+
+    runner()
+        .run(&(msg(), ctx()), |(msg, ctx)| {
+            let det1 = sk.try_sign_with_ctx(&msg, &ctx).expect("Signing failed");
+            let det2 = sk.try_sign_with_ctx(&msg, &ctx).expect("Signing failed");
+            prop_assert_eq!(&det1, &det2);
+
+            let hedged1 = sk
+                .try_sign_hedged_with_ctx(&msg, &ctx)
+                .expect("Hedged signing failed");
+            let hedged2 = sk
+                .try_sign_hedged_with_ctx(&msg, &ctx)
+                .expect("Hedged signing failed");
+            prop_assert_ne!(&hedged1, &hedged2);
+            prop_assert_ne!(&hedged1, &det1);
+
+            prop_assert!(vk.verify_with_ctx(&msg, &ctx, &hedged1).is_ok());
+            prop_assert!(vk.verify_with_ctx(&msg, &ctx, &hedged2).is_ok());
+            Ok(())
+        })
+        .unwrap();
+}
+test_parameter_sets!(prop_hedged_vs_deterministic);
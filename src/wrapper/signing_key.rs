@@ -1,21 +1,26 @@
 pub use signature::Keypair;
 pub use signature::KeypairRef;
+pub use signature::RandomizedSigner;
 pub use signature::Signer;
 
 use core::fmt;
 use generic_array::GenericArray;
 
+use super::entropy;
 use super::utils;
 use super::VerifyingKey;
+use super::EMPTY_CTX;
 use super::{ParameterSet, SignatureLen, SigningKeyLen, VerifyingKeyLen};
 use crate::ffi;
 use ffi::c_int;
+use rand_core::TryCryptoRng;
 use transcoding::AsBytes;
-use utils::rand::randombytes;
 use utils::transcoding;
 use utils::typenum::Unsigned;
 
-pub(super) const EMPTY_CTX: &[u8; 0] = &[];
+/// Largest security parameter `n` (in bytes) across all the parameter sets,
+/// i.e., the largest `addrnd` value needed for hedged signing.
+const MAX_N: usize = 32;
 
 // Derive Debug only when building tests or with debug assertions (i.e., non-release)
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
@@ -100,21 +105,41 @@ impl<P: ParameterSet> signature::Signer<super::Signature<P>> for SigningKey<P> {
 pub enum KeygenError {
     /// The FFI function returned a non-zero status code.
     FFIError(c_int),
+    /// The entropy source failed to provide the key generation seeds.
+    Entropy(entropy::EntropyError),
 }
 
 impl fmt::Display for KeygenError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             KeygenError::FFIError(code) => write!(f, "FFI keygen failed with error code {code:}"),
+            KeygenError::Entropy(e) => write!(f, "Keygen failed to gather entropy: {e:}"),
         }
     }
 }
 
+impl core::error::Error for KeygenError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            KeygenError::FFIError(_) => None,
+            KeygenError::Entropy(e) => Some(e),
+        }
+    }
+}
+
+impl From<entropy::EntropyError> for KeygenError {
+    fn from(e: entropy::EntropyError) -> Self {
+        KeygenError::Entropy(e)
+    }
+}
+
 /// Generate an new SLH-DSA keypair
 ///
 /// # Errors
 ///
-/// Returns a [`KeygenError`] if the underlying FFI key generation fails.
+/// Returns [`KeygenError::Entropy`] if the current [`entropy`](crate::entropy)
+/// source fails, or [`KeygenError::FFIError`] if the underlying FFI key
+/// generation fails otherwise.
 ///
 /// # Usage
 ///
@@ -128,7 +153,81 @@ impl fmt::Display for KeygenError {
 /// assert_eq!(public_key.as_bytes().len(), P::VERIFYING_KEY_LEN);
 /// ```
 pub fn keygen<P: ParameterSet>() -> Result<(SigningKey<P>, VerifyingKey<P>), KeygenError> {
+    // The seeds are drawn here rather than through the `rbg` callback of
+    // `slh_keygen()`, which ignores its return value.
+    keygen_with_fill::<P>(entropy::fill)
+}
+
+/// Generate an new SLH-DSA keypair, drawing the seeds from the given `rng`
+/// instead of the global [`entropy`](crate::entropy) source.
+///
+/// The three seeds (`SK.seed || SK.prf || PK.seed`) are drawn from `rng`
+/// with a single `3n`-byte request, matching the NIST KAT generators.
+///
+/// # Errors
+///
+/// Returns [`KeygenError::Entropy`] if `rng` fails, or
+/// [`KeygenError::FFIError`] if the underlying FFI key generation fails.
+///
+/// # Usage
+///
+/// ```rust
+/// # use slhdsa_c_rs::*;
+/// use slhdsa_c_rs::SLH_DSA_SHAKE_128s as P;
+///
+/// let mut rng = rand::rngs::SysRng;
+/// let (private_key, public_key) = keygen_with_rng::<P, _>(&mut rng).expect("Keygen failed");
+///
+/// assert_eq!(private_key.as_bytes().len(), P::SIGNING_KEY_LEN);
+/// assert_eq!(public_key.as_bytes().len(), P::VERIFYING_KEY_LEN);
+/// ```
+pub fn keygen_with_rng<P: ParameterSet, R: TryCryptoRng + ?Sized>(
+    rng: &mut R,
+) -> Result<(SigningKey<P>, VerifyingKey<P>), KeygenError> {
+    keygen_with_fill::<P>(|seeds| {
+        rng.try_fill_bytes(seeds)
+            .map_err(|_| entropy::EntropyError::SourceFailure)
+    })
+}
+
+/// Draw the three seeds (`SK.seed || SK.prf || PK.seed`) with a single call
+/// to `fill`, and derive the keypair from them.
+fn keygen_with_fill<P: ParameterSet>(
+    fill: impl FnOnce(&mut [u8]) -> Result<(), entropy::EntropyError>,
+) -> Result<(SigningKey<P>, VerifyingKey<P>), KeygenError> {
+    let n = P::VERIFYING_KEY_LEN / 2;
+
+    let mut seeds = [0u8; 3 * MAX_N];
+    let seeds = &mut seeds[..3 * n];
+    fill(seeds)?;
+
+    let (sk_seed, seeds) = seeds.split_at(n);
+    let (sk_prf, pk_seed) = seeds.split_at(n);
+    keygen_from_seeds::<P>(sk_seed, sk_prf, pk_seed)
+}
+
+/// Deterministically derive an SLH-DSA keypair from its seeds, as in
+/// `slh_keygen_internal()` (Algorithm 18 of FIPS 205).
+///
+/// Each seed must be `n` bytes long, i.e., half of
+/// [`VerifyingKeyLen::VERIFYING_KEY_LEN`].
+///
+/// # Errors
+///
+/// Returns [`KeygenError::FFIError`] if any seed has the wrong length, or if
+/// the underlying FFI key generation fails.
+pub fn keygen_from_seeds<P: ParameterSet>(
+    sk_seed: &[u8],
+    sk_prf: &[u8],
+    pk_seed: &[u8],
+) -> Result<(SigningKey<P>, VerifyingKey<P>), KeygenError> {
     const SUCCESS: c_int = 0;
+    const ERROR: c_int = -1;
+
+    let n = P::VERIFYING_KEY_LEN / 2;
+    if sk_seed.len() != n || sk_prf.len() != n || pk_seed.len() != n {
+        return Err(KeygenError::FFIError(ERROR));
+    }
 
     let mut sk: GenericArray<u8, <P as SigningKeyLen>::LEN> = GenericArray::default();
     let mut pk: GenericArray<u8, <P as VerifyingKeyLen>::LEN> = GenericArray::default();
@@ -136,22 +235,27 @@ pub fn keygen<P: ParameterSet>() -> Result<(SigningKey<P>, VerifyingKey<P>), Key
     let ret: c_int = {
         let prm = P::prm_as_ptr();
 
-        let sk_slice = sk.as_mut_slice();
-        let pk_slice = pk.as_mut_slice();
-
-        let sk = sk_slice.as_mut_ptr();
-        let pk = pk_slice.as_mut_ptr();
+        let sk = sk.as_mut_slice().as_mut_ptr();
+        let pk = pk.as_mut_slice().as_mut_ptr();
 
-        unsafe { crate::ffi::slh_keygen(sk, pk, Some(randombytes), prm) }
+        unsafe {
+            crate::ffi::slh_keygen_internal(
+                sk,
+                pk,
+                sk_seed.as_ptr(),
+                sk_prf.as_ptr(),
+                pk_seed.as_ptr(),
+                prm,
+            )
+        }
     };
 
-    match ret {
-        SUCCESS => (),
-        x => return Err(KeygenError::FFIError(x)),
+    if ret != SUCCESS {
+        return Err(KeygenError::FFIError(ret));
     }
 
-    // SAFETY: We assume slh_keygen fully initialized all bytes of the
-    // array, if it returned 0.
+    // SAFETY: We assume slh_keygen_internal fully initialized all bytes of
+    // the array, if it returned 0.
     let sk = SigningKey::<P> { sk };
     let pk = VerifyingKey::<P> { pk };
 
@@ -188,6 +292,9 @@ impl<P: ParameterSet> SigningKey<P> {
     /// under the associated `context` bytestring, returning a digital signature
     /// on success, or a [`signature::Error`] if something went wrong.
     ///
+    /// This produces deterministic signatures: see
+    /// [`Self::try_sign_hedged_with_ctx()`] for the hedged variant.
+    ///
     /// # Errors
     ///
     /// The main intended use case for signing errors is when communicating
@@ -200,39 +307,190 @@ impl<P: ParameterSet> SigningKey<P> {
         message: &[u8],
         context: &[u8],
     ) -> Result<super::Signature<P>, signature::Error> {
+        self.sign_inner(message, context, None)
+    }
+
+    /// Attempt to use [`Self`] to produce a hedged signature for the given
+    /// `message` bytestring under the associated `context` bytestring.
+    ///
+    /// The additional randomness (`addrnd` in FIPS 205) is drawn from the
+    /// current [`entropy`](crate::entropy) source.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`signature::Error`] if the entropy source
+    /// fails, or if the underlying FFI signature generation fails.
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// # use slhdsa_c_rs::*;
+    /// # use SLH_DSA_SHAKE_128s as P;
+    /// # let sk = SigningKey::<P>::new().expect("Keygen failed");
+    /// let msg: &[u8] = b"Hello, world!";
+    /// let ctx: &[u8] = b"Test context";
+    ///
+    /// let sig1 = sk.try_sign_hedged_with_ctx(msg, ctx).expect("Signing failed");
+    /// let sig2 = sk.try_sign_hedged_with_ctx(msg, ctx).expect("Signing failed");
+    /// assert_ne!(sig1, sig2);
+    ///
+    /// let vk: &VerifyingKey<P> = sk.as_ref();
+    /// assert!(vk.verify_with_ctx(msg, ctx, &sig1).is_ok());
+    /// assert!(vk.verify_with_ctx(msg, ctx, &sig2).is_ok());
+    /// ```
+    pub fn try_sign_hedged_with_ctx(
+        &self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<super::Signature<P>, signature::Error> {
+        Self::with_fresh_addrnd(|addrnd| self.sign_inner(message, context, Some(addrnd)))
+    }
+
+    /// Attempt to use [`Self`] to produce a hedged signature for the given
+    /// `message` bytestring under the associated `context` bytestring,
+    /// drawing the additional randomness from `rng`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`signature::Error`] if `rng` fails, or if the
+    /// underlying FFI signature generation fails.
+    pub fn try_sign_with_ctx_and_rng<R: TryCryptoRng + ?Sized>(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        rng: &mut R,
+    ) -> Result<super::Signature<P>, signature::Error> {
+        let mut addrnd = [0u8; MAX_N];
+        let addrnd = &mut addrnd[..P::VERIFYING_KEY_LEN / 2];
+        rng.try_fill_bytes(addrnd)
+            .map_err(|_| signature::Error::new())?;
+
+        self.sign_inner(message, context, Some(addrnd))
+    }
+
+    /// Draw `addrnd` (`n` bytes) from the current [`entropy`](crate::entropy)
+    /// source, and call `f` with it.
+    pub(crate) fn with_fresh_addrnd<T>(
+        f: impl FnOnce(&[u8]) -> Result<T, signature::Error>,
+    ) -> Result<T, signature::Error> {
+        let mut addrnd = [0u8; MAX_N];
+        let addrnd = &mut addrnd[..P::VERIFYING_KEY_LEN / 2];
+        entropy::fill(addrnd).map_err(|_| signature::Error::new())?;
+
+        f(addrnd)
+    }
+
+    /// Shared implementation of deterministic (`addrnd == None`) and hedged
+    /// signing.
+    pub(crate) fn sign_inner(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        addrnd: Option<&[u8]>,
+    ) -> Result<super::Signature<P>, signature::Error> {
+        self.sign_with(addrnd, |sig, sk, addrnd, prm| unsafe {
+            crate::ffi::slh_sign(
+                sig,
+                message.as_ptr(),
+                message.len(),
+                context.as_ptr(),
+                context.len(),
+                sk,
+                addrnd,
+                prm,
+            )
+        })
+    }
+
+    /// `slh_sign_internal()` (Algorithm 19 of FIPS 205): sign the already
+    /// encoded message `M'`, deterministically if `addrnd` is `None`.
+    #[cfg(feature = "acvp")]
+    pub(crate) fn sign_internal(
+        &self,
+        message: &[u8],
+        addrnd: Option<&[u8]>,
+    ) -> Result<super::Signature<P>, signature::Error> {
+        self.sign_with(addrnd, |sig, sk, addrnd, prm| unsafe {
+            crate::ffi::slh_sign_internal(sig, message.as_ptr(), message.len(), sk, addrnd, prm)
+        })
+    }
+
+    /// `hash_slh_sign()` (Algorithm 23 of FIPS 205): pre-hash `message` with
+    /// the hash function identified by `ph` (e.g., `c"SHA2-256"`), then sign
+    /// the digest under `context`.
+    #[cfg(feature = "acvp")]
+    pub(crate) fn sign_prehash(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        ph: &core::ffi::CStr,
+        addrnd: Option<&[u8]>,
+    ) -> Result<super::Signature<P>, signature::Error> {
+        self.sign_with(addrnd, |sig, sk, addrnd, prm| unsafe {
+            crate::ffi::hash_slh_sign(
+                sig,
+                message.as_ptr(),
+                message.len(),
+                context.as_ptr(),
+                context.len(),
+                ph.as_ptr(),
+                sk,
+                addrnd,
+                prm,
+            )
+        })
+    }
+
+    /// Common scaffolding around the FFI signing functions: `f` is called
+    /// with the output buffer, the secret key, the (possibly NULL) `addrnd`
+    /// and the parameter set, and must return the signature length.
+    pub(crate) fn sign_with<F>(
+        &self,
+        addrnd: Option<&[u8]>,
+        f: F,
+    ) -> Result<super::Signature<P>, signature::Error>
+    where
+        F: FnOnce(*mut u8, *const u8, *const u8, *const ffi::slh_param_s) -> usize,
+    {
         type Siglen<P> = <P as SignatureLen>::LEN;
         let mut sig: GenericArray<u8, Siglen<P>> = GenericArray::default();
 
         let ret: usize = {
             let prm = P::prm_as_ptr();
             let sk = self.sk.as_ptr();
-            let addrnd = ::core::ptr::null();
-
-            unsafe {
-                crate::ffi::slh_sign(
-                    sig.as_mut_ptr(),
-                    message.as_ptr(),
-                    message.len(),
-                    context.as_ptr(),
-                    context.len(),
-                    sk,
-                    addrnd,
-                    prm,
-                )
-            }
+            let addrnd = match addrnd {
+                // The C library reads exactly `n` bytes of `addrnd`
+                Some(addrnd) if addrnd.len() != P::VERIFYING_KEY_LEN / 2 => {
+                    return Err(signature::Error::new());
+                }
+                Some(addrnd) => addrnd.as_ptr(),
+                None => ::core::ptr::null(),
+            };
+
+            f(sig.as_mut_ptr(), sk, addrnd, prm)
         };
         if ret != <Siglen<P>>::USIZE {
             return Err(signature::Error::new());
         }
 
-        // SAFETY: We assume slh_sign fully initialized all bytes of the
-        // array, if it returned the expected siglen.
+        // SAFETY: We assume the FFI function fully initialized all bytes of
+        // the array, if it returned the expected siglen.
         let s = super::Signature::<P> { sig };
 
         Ok(s)
     }
 }
 
+impl<P: ParameterSet> signature::RandomizedSigner<super::Signature<P>> for SigningKey<P> {
+    fn try_sign_with_rng<R: TryCryptoRng + ?Sized>(
+        &self,
+        rng: &mut R,
+        msg: &[u8],
+    ) -> Result<super::Signature<P>, signature::Error> {
+        self.try_sign_with_ctx_and_rng(msg, EMPTY_CTX, rng)
+    }
+}
+
 impl<P: ParameterSet> From<SigningKey<P>> for GenericArray<u8, <P as crate::SigningKeyLen>::LEN> {
     fn from(sk: SigningKey<P>) -> Self {
         sk.sk
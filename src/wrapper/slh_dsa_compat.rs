@@ -0,0 +1,216 @@
+//! Conversions between the types of this crate and the types of the
+//! pure-Rust `RustCrypto` [`slh_dsa`] crate.
+//!
+//! Both crates encode keys and signatures as specified in FIPS 205, so keys
+//! and signatures can move freely between the two backends:
+//!
+//! ```rust
+//! use slhdsa_c_rs::*;
+//! use SLH_DSA_SHAKE_128f as P;
+//!
+//! let (sk, vk) = keygen::<P>().expect("Keygen failed");
+//!
+//! // Sign with slh-dsa, verify with slhdsa-c
+//! let their_sk = slh_dsa::SigningKey::<slh_dsa::Shake128f>::from(&sk);
+//! let msg = b"Hello, world!";
+//! let their_sig = their_sk.try_sign_with_context(msg, b"", None).unwrap();
+//!
+//! let sig = Signature::<P>::from(their_sig);
+//! assert!(vk.verify(msg, &sig).is_ok());
+//! ```
+//!
+//! Conversions are defined for each pair of matching parameter sets (e.g.,
+//! [`SLH_DSA_SHAKE_128f`](crate::SLH_DSA_SHAKE_128f) and
+//! [`slh_dsa::Shake128f`]).
+//!
+//! The list of matching parameter sets is also used by the differential
+//! tests against `slh_dsa`, which run without the `slh-dsa` feature: this
+//! module is compiled regardless, and only the conversions depend on it.
+
+#[cfg(feature = "slh-dsa")]
+use super::parameter_sets::all as sets;
+#[cfg(all(feature = "slh-dsa", feature = "sign"))]
+use super::SigningKey;
+#[cfg(feature = "slh-dsa")]
+use super::{Signature, VerifyingKey};
+
+/// Call the macro `$m` with the pairs of matching parameter sets of this
+/// crate and of `slh_dsa`, e.g. `SLH_DSA_SHAKE_128s => slh_dsa::Shake128s`,
+/// each preceded by the `cfg` attribute of its cargo feature.
+///
+/// The attributes are evaluated in the crate calling `$m`, with its own
+/// `slh_dsa`: this is only meant for this crate and its tests.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! for_each_slh_dsa_pair {
+    ($m:ident) => {
+        $m! {
+            #[cfg(feature = "shake-128s")]
+            SLH_DSA_SHAKE_128s => slh_dsa::Shake128s,
+            #[cfg(feature = "shake-128f")]
+            SLH_DSA_SHAKE_128f => slh_dsa::Shake128f,
+            #[cfg(feature = "shake-192s")]
+            SLH_DSA_SHAKE_192s => slh_dsa::Shake192s,
+            #[cfg(feature = "shake-192f")]
+            SLH_DSA_SHAKE_192f => slh_dsa::Shake192f,
+            #[cfg(feature = "shake-256s")]
+            SLH_DSA_SHAKE_256s => slh_dsa::Shake256s,
+            #[cfg(feature = "shake-256f")]
+            SLH_DSA_SHAKE_256f => slh_dsa::Shake256f,
+            #[cfg(feature = "sha2-128s")]
+            SLH_DSA_SHA2_128s => slh_dsa::Sha2_128s,
+            #[cfg(feature = "sha2-128f")]
+            SLH_DSA_SHA2_128f => slh_dsa::Sha2_128f,
+            #[cfg(feature = "sha2-192s")]
+            SLH_DSA_SHA2_192s => slh_dsa::Sha2_192s,
+            #[cfg(feature = "sha2-192f")]
+            SLH_DSA_SHA2_192f => slh_dsa::Sha2_192f,
+            #[cfg(feature = "sha2-256s")]
+            SLH_DSA_SHA2_256s => slh_dsa::Sha2_256s,
+            #[cfg(feature = "sha2-256f")]
+            SLH_DSA_SHA2_256f => slh_dsa::Sha2_256f,
+        }
+    };
+}
+
+/// The encodings are fixed-length in both crates, so decoding the encoding
+/// of a value of the matching parameter set cannot fail.
+#[cfg(feature = "slh-dsa")]
+const LEN_MISMATCH: &str = "Encoding length mismatch between matching parameter sets";
+
+#[cfg(feature = "slh-dsa")]
+macro_rules! impl_conversions {
+    ( $( #[cfg($cfg:meta)] $ours:ident => $theirs:ty ),+ $(,)? ) => {
+        $(
+            #[cfg(all($cfg, feature = "sign"))]
+            impl From<&SigningKey<sets::$ours>> for slh_dsa::SigningKey<$theirs> {
+                fn from(sk: &SigningKey<sets::$ours>) -> Self {
+                    Self::try_from(AsRef::<[u8]>::as_ref(sk)).expect(LEN_MISMATCH)
+                }
+            }
+
+            #[cfg(all($cfg, feature = "sign"))]
+            impl From<SigningKey<sets::$ours>> for slh_dsa::SigningKey<$theirs> {
+                fn from(sk: SigningKey<sets::$ours>) -> Self {
+                    Self::from(&sk)
+                }
+            }
+
+            #[cfg(all($cfg, feature = "sign"))]
+            impl From<&slh_dsa::SigningKey<$theirs>> for SigningKey<sets::$ours> {
+                fn from(sk: &slh_dsa::SigningKey<$theirs>) -> Self {
+                    Self::try_from(&sk.to_bytes()[..]).expect(LEN_MISMATCH)
+                }
+            }
+
+            #[cfg(all($cfg, feature = "sign"))]
+            impl From<slh_dsa::SigningKey<$theirs>> for SigningKey<sets::$ours> {
+                fn from(sk: slh_dsa::SigningKey<$theirs>) -> Self {
+                    Self::from(&sk)
+                }
+            }
+
+            #[cfg($cfg)]
+            impl From<&VerifyingKey<sets::$ours>> for slh_dsa::VerifyingKey<$theirs> {
+                fn from(vk: &VerifyingKey<sets::$ours>) -> Self {
+                    Self::try_from(vk.as_ref()).expect(LEN_MISMATCH)
+                }
+            }
+
+            #[cfg($cfg)]
+            impl From<VerifyingKey<sets::$ours>> for slh_dsa::VerifyingKey<$theirs> {
+                fn from(vk: VerifyingKey<sets::$ours>) -> Self {
+                    Self::from(&vk)
+                }
+            }
+
+            #[cfg($cfg)]
+            impl From<&slh_dsa::VerifyingKey<$theirs>> for VerifyingKey<sets::$ours> {
+                fn from(vk: &slh_dsa::VerifyingKey<$theirs>) -> Self {
+                    Self::try_from(&vk.to_bytes()[..]).expect(LEN_MISMATCH)
+                }
+            }
+
+            #[cfg($cfg)]
+            impl From<slh_dsa::VerifyingKey<$theirs>> for VerifyingKey<sets::$ours> {
+                fn from(vk: slh_dsa::VerifyingKey<$theirs>) -> Self {
+                    Self::from(&vk)
+                }
+            }
+
+            #[cfg($cfg)]
+            impl From<&Signature<sets::$ours>> for slh_dsa::Signature<$theirs> {
+                fn from(sig: &Signature<sets::$ours>) -> Self {
+                    Self::try_from(sig.as_ref()).expect(LEN_MISMATCH)
+                }
+            }
+
+            #[cfg($cfg)]
+            impl From<Signature<sets::$ours>> for slh_dsa::Signature<$theirs> {
+                fn from(sig: Signature<sets::$ours>) -> Self {
+                    Self::from(&sig)
+                }
+            }
+
+            #[cfg($cfg)]
+            impl From<&slh_dsa::Signature<$theirs>> for Signature<sets::$ours> {
+                fn from(sig: &slh_dsa::Signature<$theirs>) -> Self {
+                    Self::try_from(&sig.to_bytes()[..]).expect(LEN_MISMATCH)
+                }
+            }
+
+            #[cfg($cfg)]
+            impl From<slh_dsa::Signature<$theirs>> for Signature<sets::$ours> {
+                fn from(sig: slh_dsa::Signature<$theirs>) -> Self {
+                    Self::from(&sig)
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "slh-dsa")]
+for_each_slh_dsa_pair!(impl_conversions);
+
+#[cfg(all(test, feature = "sign", feature = "slh-dsa"))]
+mod tests {
+    use super::sets::*;
+    use super::*;
+    use crate::{keygen, AsBytes, Keypair, Signer, Verifier};
+
+    macro_rules! gen_compat_test {
+        ( $( #[$meta:meta] $ours:ident => $theirs:ty ),+ $(,)? ) => {
+            $(
+                paste::paste! {
+                    #[$meta]
+                    #[test]
+                    fn [<test_slh_dsa_compat_ $ours:lower>]() {
+                        let (sk, vk) = keygen::<$ours>().expect("Keygen failed");
+                        let msg = b"Hello, world!";
+
+                        let their_sk = slh_dsa::SigningKey::<$theirs>::from(&sk);
+                        let their_vk = slh_dsa::VerifyingKey::<$theirs>::from(&vk);
+                        assert_eq!(their_sk.verifying_key(), their_vk);
+
+                        // slh-dsa -> slhdsa-c
+                        let their_sig = their_sk.try_sign(msg).expect("Signing failed");
+                        let sig = Signature::<$ours>::from(&their_sig);
+                        assert!(vk.verify(msg, &sig).is_ok());
+
+                        // slhdsa-c -> slh-dsa
+                        let sig = sk.try_sign(msg).expect("Signing failed");
+                        let their_sig = slh_dsa::Signature::<$theirs>::from(&sig);
+                        assert!(their_vk.verify(msg, &their_sig).is_ok());
+
+                        // Round-trips
+                        assert_eq!(SigningKey::<$ours>::from(their_sk).as_bytes(), sk.as_bytes());
+                        assert_eq!(VerifyingKey::<$ours>::from(their_vk).as_bytes(), vk.as_bytes());
+                        assert_eq!(Signature::<$ours>::from(their_sig), sig);
+                    }
+                }
+            )+
+        };
+    }
+
+    for_each_slh_dsa_pair!(gen_compat_test);
+}
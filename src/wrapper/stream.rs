@@ -0,0 +1,455 @@
+//! Signing and verifying messages streamed from a reader.
+//!
+//! Pure SLH-DSA hashes the message twice: once with `PRF_msg` for the
+//! randomizer `R`, and once with `H_msg` for the digest that selects and
+//! signs the FORS and hypertree leaves. Both are computed incrementally in
+//! Rust here, with the hash functions of FIPS 205 (Section 11), and the C
+//! library then signs the digest (see `csrc/digest_sign.c`). The message is
+//! never held in memory as a whole: signing reads it twice from a
+//! `Read + Seek` source, rewinding it to its initial position in between,
+//! while verifying reads it once from any `Read` source.
+//!
+//! The signatures are byte-identical to [`SigningKey::try_sign_with_ctx()`]
+//! over the bytes read:
+//!
+//! ```rust
+//! # use slhdsa_c_rs::*;
+//! # use SLH_DSA_SHAKE_128f as P;
+//! use std::io::Cursor;
+//!
+//! let (sk, vk) = keygen::<P>().expect("Keygen failed");
+//! let msg: &[u8] = b"Hello, world!";
+//!
+//! let sig = sk
+//!     .try_sign_reader_with_ctx(&mut Cursor::new(msg), b"")
+//!     .expect("Signing failed");
+//! assert_eq!(sig, sk.try_sign_with_ctx(msg, b"").unwrap());
+//! assert!(vk.verify_reader_with_ctx(&mut Cursor::new(msg), b"", &sig).is_ok());
+//! ```
+//!
+//! A source whose content changes between the two passes (e.g., a file
+//! being written meanwhile) yields a signature that does not verify, for
+//! either version of the content.
+//!
+//! Like custom parameter sets, this relies on the private headers of
+//! `slhdsa-c`, and is not available with the `system` feature.
+
+use std::io::{self, Read};
+#[cfg(feature = "sign")]
+use std::io::{Seek, SeekFrom};
+
+#[cfg(feature = "sign")]
+use hmac::{digest::KeyInit, Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+use sha3::digest::{ExtendableOutput, XofReader};
+use sha3::Shake256;
+
+#[cfg(feature = "sign")]
+use super::SigningKey;
+use super::{HashFamily, ParameterSet, Signature, VerifyingKey};
+use crate::ffi::{self, c_int};
+
+/// Largest security parameter `n` (in bytes) across all the parameter sets
+#[cfg(feature = "sign")]
+const MAX_N: usize = 32;
+
+/// Largest digest length `m` (in bytes) across all the parameter sets
+const MAX_DIGEST_LEN: usize = 49;
+
+/// Size of the chunks the message is read in
+const CHUNK_LEN: usize = 8192;
+
+unsafe extern "C" {
+    fn slhdsa_rs_digest_len(prm: *const ffi::slh_param_t) -> u32;
+
+    #[cfg(feature = "sign")]
+    fn slhdsa_rs_sign_digest(
+        sig: *mut u8,
+        r: *const u8,
+        digest: *const u8,
+        sk: *const u8,
+        prm: *const ffi::slh_param_t,
+    ) -> usize;
+
+    fn slhdsa_rs_verify_digest(
+        digest: *const u8,
+        sig: *const u8,
+        sig_sz: usize,
+        pk: *const u8,
+        prm: *const ffi::slh_param_t,
+    ) -> c_int;
+}
+
+/// A message, fed in chunks to its argument on each call
+type Message<'a> = dyn FnMut(&mut dyn FnMut(&[u8])) -> Result<(), signature::Error> + 'a;
+
+/// Feed the content of `reader` to `update`, up to its end
+fn read_chunks<R: Read + ?Sized>(
+    reader: &mut R,
+    update: &mut dyn FnMut(&[u8]),
+) -> Result<(), signature::Error> {
+    let mut buf = [0u8; CHUNK_LEN];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(len) => update(&buf[..len]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(_) => return Err(signature::Error::new()),
+        }
+    }
+}
+
+/// The digest length `m` of `P`, as used by the C library
+fn digest_len<P: ParameterSet>() -> Result<usize, signature::Error> {
+    // SAFETY: `prm` is a valid parameter set
+    let len = unsafe { slhdsa_rs_digest_len(P::prm_as_ptr()) } as usize;
+    if len > MAX_DIGEST_LEN {
+        return Err(signature::Error::new());
+    }
+    Ok(len)
+}
+
+/// Run `f` with `M' = toByte(0, 1) || toByte(|ctx|, 1) || ctx || M`, the
+/// message of pure SLH-DSA (Algorithms 22 and 24 of FIPS 205)
+fn with_encoded_message<T>(
+    context: &[u8],
+    message: &mut Message<'_>,
+    f: impl FnOnce(&mut Message<'_>) -> Result<T, signature::Error>,
+) -> Result<T, signature::Error> {
+    let ctx_len = u8::try_from(context.len()).map_err(|_| signature::Error::new())?;
+    f(&mut |update: &mut dyn FnMut(&[u8])| {
+        update(&[0, ctx_len]);
+        update(context);
+        message(update)
+    })
+}
+
+/// `PRF_msg(SK.prf, opt_rand, M)`, written to `r` (`n` bytes)
+#[cfg(feature = "sign")]
+fn prf_msg(
+    family: HashFamily,
+    sk_prf: &[u8],
+    opt_rand: &[u8],
+    message: &mut Message<'_>,
+    r: &mut [u8],
+) -> Result<(), signature::Error> {
+    match family {
+        HashFamily::Shake => {
+            let mut shake = Shake256::default();
+            sha3::digest::Update::update(&mut shake, sk_prf);
+            sha3::digest::Update::update(&mut shake, opt_rand);
+            message(&mut |chunk| sha3::digest::Update::update(&mut shake, chunk))?;
+            shake.finalize_xof().read(r);
+            Ok(())
+        }
+        // Security category 1 (Section 11.2.1), then 3 and 5 (11.2.2)
+        HashFamily::Sha2 if r.len() == 16 => {
+            hmac_prf_msg::<Hmac<Sha256>>(sk_prf, opt_rand, message, r)
+        }
+        HashFamily::Sha2 => hmac_prf_msg::<Hmac<Sha512>>(sk_prf, opt_rand, message, r),
+    }
+}
+
+/// `Trunc_n(HMAC(SK.prf, opt_rand || M))`
+#[cfg(feature = "sign")]
+fn hmac_prf_msg<M: Mac + KeyInit>(
+    sk_prf: &[u8],
+    opt_rand: &[u8],
+    message: &mut Message<'_>,
+    r: &mut [u8],
+) -> Result<(), signature::Error> {
+    let mut mac = <M as Mac>::new_from_slice(sk_prf).map_err(|_| signature::Error::new())?;
+    mac.update(opt_rand);
+    message(&mut |chunk| mac.update(chunk))?;
+    r.copy_from_slice(&mac.finalize().into_bytes()[..r.len()]);
+    Ok(())
+}
+
+/// `H_msg(R, PK.seed, PK.root, M)`, written to `digest` (`m` bytes), where
+/// `pk` is `PK.seed || PK.root`
+fn h_msg(
+    family: HashFamily,
+    r: &[u8],
+    pk: &[u8],
+    message: &mut Message<'_>,
+    digest: &mut [u8],
+) -> Result<(), signature::Error> {
+    match family {
+        HashFamily::Shake => {
+            let mut shake = Shake256::default();
+            sha3::digest::Update::update(&mut shake, r);
+            sha3::digest::Update::update(&mut shake, pk);
+            message(&mut |chunk| sha3::digest::Update::update(&mut shake, chunk))?;
+            shake.finalize_xof().read(digest);
+            Ok(())
+        }
+        HashFamily::Sha2 if r.len() == 16 => sha2_h_msg::<Sha256>(r, pk, message, digest),
+        HashFamily::Sha2 => sha2_h_msg::<Sha512>(r, pk, message, digest),
+    }
+}
+
+/// `MGF1(R || PK.seed || SHA(R || PK.seed || PK.root || M), m)`
+fn sha2_h_msg<D: Digest>(
+    r: &[u8],
+    pk: &[u8],
+    message: &mut Message<'_>,
+    digest: &mut [u8],
+) -> Result<(), signature::Error> {
+    let pk_seed = &pk[..r.len()];
+
+    let mut hash = D::new();
+    hash.update(r);
+    hash.update(pk);
+    message(&mut |chunk| hash.update(chunk))?;
+    let inner = hash.finalize();
+
+    for (counter, block) in (0u32..).zip(digest.chunks_mut(<D as Digest>::output_size())) {
+        let mut hash = D::new();
+        hash.update(r);
+        hash.update(pk_seed);
+        hash.update(&inner);
+        hash.update(counter.to_be_bytes());
+        block.copy_from_slice(&hash.finalize()[..block.len()]);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sign")]
+impl<P: ParameterSet> SigningKey<P> {
+    /// Deterministically sign the content of `reader`, from its current
+    /// position to its end, under `context`, see
+    /// [`Self::try_sign_with_ctx()`]
+    ///
+    /// `reader` is read twice, and left at its end.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if reading or seeking fails, or if
+    /// signing fails.
+    pub fn try_sign_reader_with_ctx<R: Read + Seek + ?Sized>(
+        &self,
+        reader: &mut R,
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        self.sign_reader(reader, context, None)
+    }
+
+    /// Produce a hedged signature of the content of `reader`, from its
+    /// current position to its end, under `context`, see
+    /// [`Self::try_sign_hedged_with_ctx()`]
+    ///
+    /// `reader` is read twice, and left at its end.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if reading or seeking fails, or if
+    /// the entropy source or signing fails.
+    pub fn try_sign_reader_hedged_with_ctx<R: Read + Seek + ?Sized>(
+        &self,
+        reader: &mut R,
+        context: &[u8],
+    ) -> Result<Signature<P>, signature::Error> {
+        Self::with_fresh_addrnd(|addrnd| self.sign_reader(reader, context, Some(addrnd)))
+    }
+
+    fn sign_reader<R: Read + Seek + ?Sized>(
+        &self,
+        reader: &mut R,
+        context: &[u8],
+        addrnd: Option<&[u8]>,
+    ) -> Result<Signature<P>, signature::Error> {
+        let start = reader
+            .stream_position()
+            .map_err(|_| signature::Error::new())?;
+        self.sign_streamed(context, addrnd, &mut |update| {
+            reader
+                .seek(SeekFrom::Start(start))
+                .map_err(|_| signature::Error::new())?;
+            read_chunks(reader, update)
+        })
+    }
+
+    /// Sign the message fed by `message` under `context`, deterministically
+    /// if `addrnd` is `None`. `message` is called twice, and must feed the
+    /// same bytes each time.
+    pub(crate) fn sign_streamed(
+        &self,
+        context: &[u8],
+        addrnd: Option<&[u8]>,
+        message: &mut Message<'_>,
+    ) -> Result<Signature<P>, signature::Error> {
+        let n = P::VERIFYING_KEY_LEN / 2;
+        let (sk_prf, pk) = (&self.sk[n..2 * n], &self.sk[2 * n..]);
+        let opt_rand = match addrnd {
+            Some(addrnd) if addrnd.len() != n => return Err(signature::Error::new()),
+            Some(addrnd) => addrnd,
+            // `PK.seed` for deterministic signing
+            None => &pk[..n],
+        };
+
+        let mut r = [0u8; MAX_N];
+        let r = &mut r[..n];
+        let mut digest = [0u8; MAX_DIGEST_LEN];
+        let digest = &mut digest[..digest_len::<P>()?];
+        with_encoded_message(context, message, |message| {
+            prf_msg(P::HASH_FAMILY, sk_prf, opt_rand, message, r)?;
+            h_msg(P::HASH_FAMILY, r, pk, message, digest)
+        })?;
+
+        self.sign_with(None, |sig, sk, _, prm| unsafe {
+            slhdsa_rs_sign_digest(sig, r.as_ptr(), digest.as_ptr(), sk, prm)
+        })
+    }
+}
+
+impl<P: ParameterSet> VerifyingKey<P> {
+    /// Verify `signature` of the content of `reader`, from its current
+    /// position to its end, under `context`, see
+    /// [`Self::verify_with_ctx()`]
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`signature::Error`] if reading fails, or if the signature
+    /// is inauthentic.
+    pub fn verify_reader_with_ctx<R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+        context: &[u8],
+        signature: &Signature<P>,
+    ) -> Result<(), signature::Error> {
+        self.verify_streamed(context, signature, &mut |update| {
+            read_chunks(reader, update)
+        })
+    }
+
+    /// Verify `signature` of the message fed by `message` under `context`.
+    /// `message` is called once.
+    pub(crate) fn verify_streamed(
+        &self,
+        context: &[u8],
+        signature: &Signature<P>,
+        message: &mut Message<'_>,
+    ) -> Result<(), signature::Error> {
+        let n = P::VERIFYING_KEY_LEN / 2;
+        let sig = &signature.sig;
+
+        let mut digest = [0u8; MAX_DIGEST_LEN];
+        let digest = &mut digest[..digest_len::<P>()?];
+        with_encoded_message(context, message, |message| {
+            h_msg(P::HASH_FAMILY, &sig[..n], &self.pk, message, digest)
+        })?;
+
+        self.verify_with(|pk, prm| unsafe {
+            slhdsa_rs_verify_digest(digest.as_ptr(), sig.as_ptr(), sig.len(), pk, prm)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(all(feature = "sign", slhdsa_fast_sets))]
+    use crate::wrapper::utils::macros::{gen_test, test_fast_parameter_sets};
+    #[cfg(all(feature = "sign", slhdsa_fast_sets))]
+    use crate::*;
+    #[cfg(all(feature = "sign", slhdsa_fast_sets))]
+    use std::io::Cursor;
+    use std::vec::Vec;
+
+    // Known answers from the tests of the RustCrypto `slh-dsa` crate, with
+    // `SK.prf = 0^n`, `opt_rand = 1^n` and `M = 2^32`
+    #[cfg(feature = "sign")]
+    #[test]
+    fn test_prf_msg() {
+        for (family, expected) in [
+            (HashFamily::Shake, "bc5c062307df0a41aeeae19ad655f7b2"),
+            (HashFamily::Sha2, "6a4b5cf23911d4f3a6591d7003445316"),
+        ] {
+            let msg = [2u8; 32];
+            let mut r = [0u8; 16];
+            prf_msg(
+                family,
+                &[0; 16],
+                &[1; 16],
+                &mut |update| read_chunks(&mut (&msg[..5]).chain(&msg[5..]), update),
+                &mut r,
+            )
+            .unwrap();
+            assert_eq!(hex::encode(r), expected, "{family:?}");
+        }
+    }
+
+    // Same, with `R = 0^n`, `PK.seed = 1^n`, `PK.root = 2^n` and `M = 3^32`,
+    // exercising MGF1 with SHA-256 and SHA-512
+    #[test]
+    fn test_h_msg() {
+        const SHA256_EXPECTED: &str =
+            "56658221f675d907a309255e8faef639d11e6a1118fa05d3bbd26179a7e0a54a7f5b";
+        const SHA512_EXPECTED: &str = "8c86dfb66392d1b647df0deab90be68fb6f988513e84d3ef75fa6859\
+                                       1122bb5d74f6413672db5164e56492b7ca2c2e0335";
+
+        for (n, expected) in [(16, SHA256_EXPECTED), (32, SHA512_EXPECTED)] {
+            let msg = [3u8; 32];
+            let pk: Vec<u8> = [[1u8; 32], [2u8; 32]]
+                .iter()
+                .flat_map(|part| &part[..n])
+                .copied()
+                .collect();
+            let mut digest = std::vec![0u8; expected.len() / 2];
+            h_msg(
+                HashFamily::Sha2,
+                &[0; 32][..n],
+                &pk,
+                &mut |update| read_chunks(&mut &msg[..], update),
+                &mut digest,
+            )
+            .unwrap();
+            assert_eq!(hex::encode(digest), expected, "n = {n}");
+        }
+    }
+
+    #[cfg(all(feature = "sign", slhdsa_fast_sets))]
+    fn test_sign_reader<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let ctx = b"stream";
+        let content: Vec<u8> = (0..3 * CHUNK_LEN + 100)
+            .map(|i| u8::try_from(i % 251).unwrap())
+            .collect();
+
+        for (start, end) in [(0, 0), (0, 1), (0, content.len()), (100, content.len())] {
+            let message = &content[start..end];
+            let mut reader = Cursor::new(&content[..end]);
+            reader.set_position(start as u64);
+
+            let sig = sk
+                .try_sign_reader_with_ctx(&mut reader, ctx)
+                .expect("Signing failed");
+            assert_eq!(sig, sk.try_sign_with_ctx(message, ctx).unwrap());
+            assert_eq!(reader.position(), end as u64);
+
+            let mut reader = Cursor::new(message);
+            vk.verify_reader_with_ctx(&mut reader, ctx, &sig)
+                .expect("Verification failed");
+            assert!(vk
+                .verify_reader_with_ctx(&mut Cursor::new(message), b"", &sig)
+                .is_err());
+
+            let mut reader = Cursor::new(message);
+            let sig = sk
+                .try_sign_reader_hedged_with_ctx(&mut reader, ctx)
+                .expect("Signing failed");
+            vk.verify_with_ctx(message, ctx, &sig)
+                .expect("Verification failed");
+            assert!(vk
+                .verify_reader_with_ctx(&mut Cursor::new(message), ctx, &sig)
+                .is_ok());
+        }
+
+        let long_ctx = [0u8; 256];
+        let mut reader = Cursor::new(&content[..]);
+        assert!(sk.try_sign_reader_with_ctx(&mut reader, &long_ctx).is_err());
+    }
+
+    #[cfg(all(feature = "sign", slhdsa_fast_sets))]
+    test_fast_parameter_sets!(test_sign_reader);
+}
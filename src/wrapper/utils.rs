@@ -1,4 +1,3 @@
 pub(crate) mod macros;
-pub(crate) mod rand;
 pub mod transcoding;
 pub(crate) mod typenum;
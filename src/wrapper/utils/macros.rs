@@ -1,17 +1,27 @@
 macro_rules! define_param_set {
-    // name, oid, sizes + their typenum types, chosen struct ident, chosen ffi ident
+    // name, oid, sizes + their typenum types, chosen struct ident, chosen ffi ident,
+    // hash family
     ($name:literal, $oid:literal,
      $vk_len:literal, $vk_len_ty:ty,
      $sk_len:literal, $sk_len_ty:ty,
      $sig_len:literal, $sig_len_ty:ty,
-     $TypeIdent:ident, $ffi_path:path) => {
+     $TypeIdent:ident, $ffi_path:path, $family:ident) => {
         #[allow(non_camel_case_types)]
         #[derive(PartialEq, Eq, Clone, Debug)]
         #[doc = concat!("Implements ", $name, " as described in NIST [FIPS 205](https://csrc.nist.gov/pubs/fips/205/final).")]
         pub struct $TypeIdent {}
         impl FFIParams for $TypeIdent {
+            const HASH_FAMILY: $crate::wrapper::HashFamily = $crate::wrapper::HashFamily::$family;
+
             fn prm() -> &'static ffi::slh_param_s {
-                unsafe { &$ffi_path }
+                let prm = unsafe { &$ffi_path };
+                #[cfg(feature = "system")]
+                {
+                    static CHECKED: core::sync::atomic::AtomicBool =
+                        core::sync::atomic::AtomicBool::new(false);
+                    crate::wrapper::check_system_sizes::<Self>(prm, &CHECKED);
+                }
+                prm
             }
         }
         impl SignatureLen for $TypeIdent {
@@ -34,6 +44,9 @@ macro_rules! define_param_set {
 }
 pub(crate) use define_param_set;
 
+#[cfg(test)]
+pub(crate) use macros_for_tests::*;
+
 #[cfg(test)]
 pub(crate) mod macros_for_tests {
     /// Generate a test case
@@ -51,22 +64,58 @@ pub(crate) mod macros_for_tests {
 
     macro_rules! test_parameter_sets {
         ($name:ident) => {
+            #[cfg(feature = "sha2-128s")]
             gen_test!($name, SLH_DSA_SHA2_128s);
+            #[cfg(feature = "sha2-128f")]
             gen_test!($name, SLH_DSA_SHA2_128f);
+            #[cfg(feature = "sha2-192s")]
             gen_test!($name, SLH_DSA_SHA2_192s);
+            #[cfg(feature = "sha2-192f")]
             gen_test!($name, SLH_DSA_SHA2_192f);
+            #[cfg(feature = "sha2-256s")]
             gen_test!($name, SLH_DSA_SHA2_256s);
+            #[cfg(feature = "sha2-256f")]
             gen_test!($name, SLH_DSA_SHA2_256f);
 
+            #[cfg(feature = "shake-128s")]
             gen_test!($name, SLH_DSA_SHAKE_128s);
+            #[cfg(feature = "shake-128f")]
             gen_test!($name, SLH_DSA_SHAKE_128f);
+            #[cfg(feature = "shake-192s")]
             gen_test!($name, SLH_DSA_SHAKE_192s);
+            #[cfg(feature = "shake-192f")]
             gen_test!($name, SLH_DSA_SHAKE_192f);
+            #[cfg(feature = "shake-256s")]
             gen_test!($name, SLH_DSA_SHAKE_256s);
+            #[cfg(feature = "shake-256f")]
             gen_test!($name, SLH_DSA_SHAKE_256f);
         };
     }
     pub(crate) use test_parameter_sets;
+
+    /// Like `test_parameter_sets!`, for the "f" parameter sets only: signing
+    /// with the "s" ones is too slow for tests signing many messages.
+    ///
+    /// `build.rs` sets `cfg(slhdsa_fast_sets)` when any of them is enabled,
+    /// to gate the code only these tests use.
+    #[allow(unused_macros)] // Only used with optional features
+    macro_rules! test_fast_parameter_sets {
+        ($name:ident) => {
+            #[cfg(feature = "sha2-128f")]
+            gen_test!($name, SLH_DSA_SHA2_128f);
+            #[cfg(feature = "sha2-192f")]
+            gen_test!($name, SLH_DSA_SHA2_192f);
+            #[cfg(feature = "sha2-256f")]
+            gen_test!($name, SLH_DSA_SHA2_256f);
+
+            #[cfg(feature = "shake-128f")]
+            gen_test!($name, SLH_DSA_SHAKE_128f);
+            #[cfg(feature = "shake-192f")]
+            gen_test!($name, SLH_DSA_SHAKE_192f);
+            #[cfg(feature = "shake-256f")]
+            gen_test!($name, SLH_DSA_SHAKE_256f);
+        };
+    }
+    #[allow(unused_imports)]
+    pub(crate) use test_fast_parameter_sets;
 }
-#[cfg(test)]
-pub(crate) use macros_for_tests::*;
@@ -17,18 +17,10 @@ macro_rules! u_from_bits {
     (1 $($rest:tt)*) => { UInt<u_from_bits!($($rest)*), B1> };
 }
 
-// 7856 (0b1111010110000)
-pub type U7856 = u_from_bits!(0 0 0 0 1 1 0 1 0 1 1 1 1);
-// 16224 (0b11111101100000)
-pub type U16224 = u_from_bits!(0 0 0 0 0 1 1 0 1 1 1 1 1 1);
-// 17088 (0b100001011000000)
-pub type U17088 = u_from_bits!(0 0 0 0 0 0 1 1 0 1 0 0 0 0 1);
-// 29792 (0b111010001100000)
-pub type U29792 = u_from_bits!(0 0 0 0 0 1 1 0 0 0 1 0 1 1 1);
-// 35664 (0b1000101101010000)
-pub type U35664 = u_from_bits!(0 0 0 0 1 0 1 0 1 1 0 1 0 0 0 1);
-// 49856 (0b1100001011000000)
-pub type U49856 = u_from_bits!(0 0 0 0 0 0 1 1 0 1 0 0 0 0 1 1);
+// The signature sizes of the parameter sets, generated by `build.rs`. They
+// are unused when the parameter sets using them are disabled by the cargo
+// features.
+include!(concat!(env!("OUT_DIR"), "/typenum_sizes.rs"));
 
 #[cfg(test)]
 mod test {
@@ -1,4 +1,4 @@
-pub(super) use super::signing_key::EMPTY_CTX;
+use super::EMPTY_CTX;
 pub use signature::Verifier;
 
 use super::utils::typenum::Unsigned;
@@ -30,24 +30,96 @@ impl<P: ParameterSet> VerifyingKey<P> {
         context: &[u8],
         signature: &super::Signature<P>,
     ) -> Result<(), signature::Error> {
+        let sig = signature.as_bytes();
+        self.verify_with(|pk, prm| unsafe {
+            crate::ffi::slh_verify(
+                message.as_ptr(),
+                message.len(),
+                sig.as_ptr(),
+                sig.len(),
+                context.as_ptr(),
+                context.len(),
+                pk,
+                prm,
+            )
+        })
+    }
+
+    /// `slh_verify_internal()` (Algorithm 20 of FIPS 205): verify a
+    /// signature over the already encoded message `M'`.
+    ///
+    /// `signature` is taken as raw bytes, as in test vectors: signatures of
+    /// the wrong length are rejected before calling the C library, which
+    /// reads the randomizer `R` before checking the length.
+    #[cfg(feature = "acvp")]
+    pub(crate) fn verify_internal(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), signature::Error> {
+        Self::check_signature_len(signature)?;
+        self.verify_with(|pk, prm| unsafe {
+            crate::ffi::slh_verify_internal(
+                message.as_ptr(),
+                message.len(),
+                signature.as_ptr(),
+                signature.len(),
+                pk,
+                prm,
+            )
+        })
+    }
+
+    /// `hash_slh_verify()` (Algorithm 25 of FIPS 205): verify a pre-hash
+    /// signature, using the hash function identified by `ph` (e.g.,
+    /// `c"SHA2-256"`). Like [`Self::verify_internal()`], `signature` can
+    /// have any length.
+    #[cfg(feature = "acvp")]
+    pub(crate) fn verify_prehash(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        ph: &core::ffi::CStr,
+        signature: &[u8],
+    ) -> Result<(), signature::Error> {
+        Self::check_signature_len(signature)?;
+        self.verify_with(|pk, prm| unsafe {
+            crate::ffi::hash_slh_verify(
+                message.as_ptr(),
+                message.len(),
+                signature.as_ptr(),
+                signature.len(),
+                context.as_ptr(),
+                context.len(),
+                ph.as_ptr(),
+                pk,
+                prm,
+            )
+        })
+    }
+
+    /// Reject raw signatures of the wrong length
+    #[cfg(feature = "acvp")]
+    fn check_signature_len(signature: &[u8]) -> Result<(), signature::Error> {
+        if signature.len() != P::SIGNATURE_LEN {
+            return Err(signature::Error::default());
+        }
+        Ok(())
+    }
+
+    /// Common scaffolding around the FFI verification functions: `f` is
+    /// called with the public key and the parameter set, and must return
+    /// the C status code.
+    pub(crate) fn verify_with<F>(&self, f: F) -> Result<(), signature::Error>
+    where
+        F: FnOnce(*const u8, *const crate::ffi::slh_param_s) -> c_int,
+    {
         const SUCCESS: c_int = 1;
         let ret = {
             let prm = P::prm_as_ptr();
             let pk = self.pk.as_ptr();
-            let sig = signature.as_bytes();
-
-            unsafe {
-                crate::ffi::slh_verify(
-                    message.as_ptr(),
-                    message.len(),
-                    sig.as_ptr(),
-                    sig.len(),
-                    context.as_ptr(),
-                    context.len(),
-                    pk,
-                    prm,
-                )
-            }
+
+            f(pk, prm)
         };
         if ret != SUCCESS {
             return Err(signature::Error::default());
@@ -97,6 +169,7 @@ impl<P: ParameterSet> AsRef<[u8]> for VerifyingKey<P> {
 ///
 /// This should be used only for internal zero-cost abstractions, relying on the
 /// fact that `VeryfingKey` is `#[repr(transparent)]`
+#[cfg(feature = "sign")]
 #[inline]
 pub(super) unsafe fn vk_from_inner<P: ParameterSet>(
     inner: &GenericArray<u8, <P as crate::VerifyingKeyLen>::LEN>,
@@ -106,3 +179,34 @@ pub(super) unsafe fn vk_from_inner<P: ParameterSet>(
     let ptr = ptr.cast::<super::verifying_key::VerifyingKey<P>>();
     &*(ptr)
 }
+
+#[cfg(all(test, feature = "acvp", slhdsa_fast_sets))]
+mod tests {
+    use crate::wrapper::utils::macros::{gen_test, test_fast_parameter_sets};
+    use crate::*;
+
+    /// Raw signatures of the wrong length are rejected, not read past
+    fn test_raw_signature_len<P: ParameterSet>() {
+        let (sk, vk) = keygen::<P>().expect("Keygen failed");
+        let msg = b"Hello, world!";
+        let ph = c"SHA2-256";
+        let internal = sk.sign_internal(msg, None).expect("Signing failed");
+        let prehash = sk.sign_prehash(msg, b"", ph, None).expect("Signing failed");
+        vk.verify_internal(msg, internal.as_ref())
+            .expect("Verification failed");
+        vk.verify_prehash(msg, b"", ph, prehash.as_ref())
+            .expect("Verification failed");
+
+        for len in [0, 1, P::SIGNATURE_LEN - 1, P::SIGNATURE_LEN + 1] {
+            let resized = |sig: &Signature<P>| {
+                let mut raw = sig.as_ref().to_vec();
+                raw.resize(len, 0);
+                raw
+            };
+            assert!(vk.verify_internal(msg, &resized(&internal)).is_err());
+            assert!(vk.verify_prehash(msg, b"", ph, &resized(&prehash)).is_err());
+        }
+    }
+
+    test_fast_parameter_sets!(test_raw_signature_len);
+}
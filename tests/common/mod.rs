@@ -21,6 +21,7 @@ fn try_init_logging() -> OurResult<()> {
     INIT.call_once(|| {
         //#[cfg(feature = "env_logger")]
         inner_try_init_logging().expect("Failed to initialize the logging system");
+        log::info!("{}", slhdsa_c_rs::build_info::BUILD_INFO);
     });
 
     Ok(())
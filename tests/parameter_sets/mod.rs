@@ -5,11 +5,13 @@ pub(crate) trait ParameterSet {
 }
 
 macro_rules! define_param_set {
-    ( $( $name:ident => $binding:ident ),+ $(,)? ) => {
+    ( $( $(#[$meta:meta])* $name:ident => $binding:ident ),+ $(,)? ) => {
         $(
+            $(#[$meta])*
             #[expect(non_camel_case_types)]
             pub(crate) struct $name;
 
+            $(#[$meta])*
             impl ParameterSet for $name {
                 fn ptr() -> &'static ffi::slh_param_s {
                     unsafe { &ffi::$binding }
@@ -20,17 +22,29 @@ macro_rules! define_param_set {
 }
 
 define_param_set! {
+    #[cfg(feature = "shake-128s")]
     SLH_DSA_SHAKE_128s => slh_dsa_shake_128s,
+    #[cfg(feature = "shake-128f")]
     SLH_DSA_SHAKE_128f => slh_dsa_shake_128f,
+    #[cfg(feature = "shake-192s")]
     SLH_DSA_SHAKE_192s => slh_dsa_shake_192s,
+    #[cfg(feature = "shake-192f")]
     SLH_DSA_SHAKE_192f => slh_dsa_shake_192f,
+    #[cfg(feature = "shake-256s")]
     SLH_DSA_SHAKE_256s => slh_dsa_shake_256s,
+    #[cfg(feature = "shake-256f")]
     SLH_DSA_SHAKE_256f => slh_dsa_shake_256f,
 
+    #[cfg(feature = "sha2-128s")]
     SLH_DSA_SHA2_128s => slh_dsa_sha2_128s,
+    #[cfg(feature = "sha2-128f")]
     SLH_DSA_SHA2_128f => slh_dsa_sha2_128f,
+    #[cfg(feature = "sha2-192s")]
     SLH_DSA_SHA2_192s => slh_dsa_sha2_192s,
+    #[cfg(feature = "sha2-192f")]
     SLH_DSA_SHA2_192f => slh_dsa_sha2_192f,
+    #[cfg(feature = "sha2-256s")]
     SLH_DSA_SHA2_256s => slh_dsa_sha2_256s,
+    #[cfg(feature = "sha2-256f")]
     SLH_DSA_SHA2_256f => slh_dsa_sha2_256f,
 }
@@ -0,0 +1,202 @@
+#![cfg(all(
+    feature = "acvp",
+    any(
+        feature = "sha2-128s",
+        feature = "sha2-128f",
+        feature = "sha2-192s",
+        feature = "sha2-192f",
+        feature = "sha2-256s",
+        feature = "sha2-256f",
+        feature = "shake-128s",
+        feature = "shake-128f",
+        feature = "shake-192s",
+        feature = "shake-192f",
+        feature = "shake-256s",
+        feature = "shake-256f"
+    )
+))]
+
+//! Run the NIST ACVP SLH-DSA JSON test vectors through the wrapper.
+//!
+//! A few vector sets, in the `prompt.json`/`expectedResults.json` format,
+//! are shipped in `tests/acvp/sample/` and always run.
+//!
+//! The full vector sets are looked up in the directory pointed to by the
+//! `SLHDSA_ACVP_DIR` environment variable, or in `tests/acvp/` by default:
+//! every `*.json` file in that directory, and every subdirectory containing
+//! an `internalProjection.json` file, or a `prompt.json` and
+//! `expectedResults.json` pair (matching the layout of
+//! `gen-val/json-files/` in the ACVP-Server repository, e.g.
+//! `SLH-DSA-sigGen-FIPS205/`), is run. These are not shipped with the crate,
+//! so this test is ignored by default: run it with `--ignored`, where
+//! finding no vector set fails the test. See `tests/acvp/README.md`.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use slhdsa_c_rs::acvp::{self, Report};
+
+fn acvp_dir() -> PathBuf {
+    match std::env::var_os("SLHDSA_ACVP_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("acvp"),
+    }
+}
+
+/// The vector sets found in `dir`: `*.json` files, and subdirectories with
+/// an `internalProjection.json` or a `prompt.json` file
+fn vector_sets(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut sets: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            if path.is_dir() {
+                path.join("internalProjection.json").is_file() || path.join("prompt.json").is_file()
+            } else {
+                path.extension().is_some_and(|ext| ext == "json")
+            }
+        })
+        .collect();
+    sets.sort();
+    sets
+}
+
+fn read(path: &Path) -> String {
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()))
+}
+
+fn run_vector_set(path: &Path) -> Report {
+    let report = if !path.is_dir() {
+        acvp::run_json(&read(path))
+    } else if path.join("internalProjection.json").is_file() {
+        acvp::run_json(&read(&path.join("internalProjection.json")))
+    } else {
+        acvp::run_prompt(
+            &read(&path.join("prompt.json")),
+            &read(&path.join("expectedResults.json")),
+        )
+    };
+    let report = report.unwrap_or_else(|e| panic!("Failed to run {}: {e}", path.display()));
+
+    log::info!("{}:\n{report}", path.display());
+    report
+}
+
+/// Run every vector set in `dir`, and return the number of test cases that
+/// passed
+fn run_vector_sets(dir: &Path) -> usize {
+    let sets = vector_sets(dir);
+    assert!(!sets.is_empty(), "No ACVP vector sets in {}", dir.display());
+
+    let (mut passed, mut failed) = (0, 0);
+    for path in sets {
+        let report = run_vector_set(&path);
+        passed += report.passed();
+        failed += report.failed();
+    }
+    assert_eq!(failed, 0, "Some ACVP test cases failed");
+    passed
+}
+
+#[test]
+#[ignore = "needs the ACVP vector sets, see tests/acvp/README.md"]
+fn acvp_vector_sets() {
+    common::setup().expect("Failed during initial setup");
+
+    run_vector_sets(&acvp_dir());
+}
+
+#[test]
+#[cfg(any(feature = "shake-128f", feature = "sha2-128f"))]
+fn acvp_sample_vector_sets() {
+    common::setup().expect("Failed during initial setup");
+
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("acvp")
+        .join("sample");
+    let passed = run_vector_sets(&dir);
+    assert!(passed > 0, "No sample test case was run");
+}
+
+#[test]
+#[cfg(feature = "shake-128s")]
+fn acvp_sigver_sample() {
+    use slhdsa_c_rs::acvp::{Mode, Outcome};
+    use slhdsa_c_rs::*;
+
+    common::setup().expect("Failed during initial setup");
+
+    type P = SLH_DSA_SHAKE_128s;
+    let (sk, vk) = keygen::<P>().expect("Keygen failed");
+    let msg = b"Hello, world!";
+    let ctx = b"Test context";
+    let sig = sk.try_sign_with_ctx(msg, ctx).expect("Signing failed");
+
+    let mut bad_sig = sig.as_bytes().to_vec();
+    bad_sig[0] ^= 0xff;
+
+    let json = format!(
+        r#"{{
+            "vsId": 0,
+            "algorithm": "SLH-DSA",
+            "mode": "sigVer",
+            "revision": "FIPS205",
+            "testGroups": [{{
+                "tgId": 1,
+                "testType": "AFT",
+                "parameterSet": "{name}",
+                "signatureInterface": "external",
+                "preHash": "pure",
+                "tests": [
+                    {{ "tcId": 1, "testPassed": true, "pk": "{pk}", "message": "{msg}", "context": "{ctx}", "signature": "{sig}" }},
+                    {{ "tcId": 2, "testPassed": false, "pk": "{pk}", "message": "{msg}", "context": "{ctx}", "signature": "{bad_sig}" }},
+                    {{ "tcId": 3, "testPassed": true, "pk": "{pk}", "message": "{msg}", "context": "", "signature": "{sig}" }}
+                ]
+            }}, {{
+                "tgId": 2,
+                "testType": "AFT",
+                "parameterSet": "SLH-DSA-UNKNOWN",
+                "tests": [{{ "tcId": 4, "testPassed": true }}]
+            }}]
+        }}"#,
+        name = P::NAME,
+        pk = hex::encode(vk.as_bytes()),
+        msg = hex::encode(msg),
+        ctx = hex::encode(ctx),
+        sig = hex::encode(sig.as_bytes()),
+        bad_sig = hex::encode(&bad_sig),
+    );
+
+    let report = acvp::run_json(&json).expect("Failed to run the vector set");
+    log::info!("{report}");
+
+    assert_eq!(report.mode, Mode::SigVer);
+    assert_eq!(report.results.len(), 4);
+    assert_eq!(report.results[0].outcome, Outcome::Passed);
+    assert_eq!(report.results[1].outcome, Outcome::Passed);
+    // The wrong context makes verification fail, contrary to `testPassed`
+    assert!(matches!(report.results[2].outcome, Outcome::Failed(_)));
+    assert!(matches!(report.results[3].outcome, Outcome::Skipped(_)));
+    assert_eq!(
+        (report.passed(), report.failed(), report.skipped()),
+        (2, 1, 1)
+    );
+}
+
+#[test]
+fn acvp_rejects_other_algorithms() {
+    let json = r#"{ "algorithm": "ML-DSA", "mode": "sigVer", "testGroups": [] }"#;
+    assert!(acvp::run_json(json).is_err());
+
+    let json = r#"{ "algorithm": "SLH-DSA", "mode": "encapDecap", "testGroups": [] }"#;
+    assert!(acvp::run_json(json).is_err());
+}
@@ -1,3 +1,19 @@
+// Needs at least one parameter set
+#![cfg(any(
+    feature = "sha2-128s",
+    feature = "sha2-128f",
+    feature = "sha2-192s",
+    feature = "sha2-192f",
+    feature = "sha2-256s",
+    feature = "sha2-256f",
+    feature = "shake-128s",
+    feature = "shake-128f",
+    feature = "shake-192s",
+    feature = "shake-192f",
+    feature = "shake-256s",
+    feature = "shake-256f"
+))]
+
 mod common;
 mod helpers;
 mod parameter_sets;
@@ -111,9 +127,10 @@ fn test_ffi_sign_verify<P: ParameterSet>() {
 }
 
 macro_rules! gen_basic_binding_tests {
-    ( $( $ty:ident ),+ $(,)? ) => {
+    ( $( $(#[$meta:meta])* $ty:ident ),+ $(,)? ) => {
         $(
             paste::paste! {
+                $(#[$meta])*
                 #[test]
                 fn [<basic_bindings_test_ $ty:lower>]() {
                     common::setup().expect("Failed during initial setup");
@@ -125,16 +142,28 @@ macro_rules! gen_basic_binding_tests {
 }
 
 gen_basic_binding_tests!(
+    #[cfg(feature = "shake-128s")]
     SLH_DSA_SHAKE_128s,
+    #[cfg(feature = "shake-128f")]
     SLH_DSA_SHAKE_128f,
+    #[cfg(feature = "shake-192s")]
     SLH_DSA_SHAKE_192s,
+    #[cfg(feature = "shake-192f")]
     SLH_DSA_SHAKE_192f,
+    #[cfg(feature = "shake-256s")]
     SLH_DSA_SHAKE_256s,
+    #[cfg(feature = "shake-256f")]
     SLH_DSA_SHAKE_256f,
+    #[cfg(feature = "sha2-128s")]
     SLH_DSA_SHA2_128s,
+    #[cfg(feature = "sha2-128f")]
     SLH_DSA_SHA2_128f,
+    #[cfg(feature = "sha2-192s")]
     SLH_DSA_SHA2_192s,
+    #[cfg(feature = "sha2-192f")]
     SLH_DSA_SHA2_192f,
+    #[cfg(feature = "sha2-256s")]
     SLH_DSA_SHA2_256s,
+    #[cfg(feature = "sha2-256f")]
     SLH_DSA_SHA2_256f,
 );
@@ -0,0 +1,39 @@
+#![cfg(feature = "bindgen")]
+
+//! Check that the pre-generated `src/ffi/bindings.rs` is in sync with the
+//! bindings generated at build time from the `slhdsa-c` header.
+
+const GENERATED: &str = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+const COMMITTED: &str = include_str!("../src/ffi/bindings.rs");
+
+/// Drop the `rust-bindgen` version banner and blank lines
+fn normalize(bindings: &str) -> Vec<&str> {
+    bindings
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !line.starts_with("/* automatically generated"))
+        .collect()
+}
+
+#[test]
+fn committed_bindings_match_header() {
+    let generated = normalize(GENERATED);
+    let committed = normalize(COMMITTED);
+
+    let first_mismatch = generated
+        .iter()
+        .zip(&committed)
+        .position(|(g, c)| g != c)
+        .unwrap_or(generated.len().min(committed.len()));
+
+    assert!(
+        generated == committed,
+        "src/ffi/bindings.rs is out of date (first difference at line {} of the \
+         normalized bindings: generated {:?}, committed {:?}).\n\
+         Regenerate it with:\n    cp {}/bindings.rs src/ffi/bindings.rs",
+        first_mismatch + 1,
+        generated.get(first_mismatch),
+        committed.get(first_mismatch),
+        env!("OUT_DIR"),
+    );
+}
@@ -1,4 +1,4 @@
-#![cfg(feature = "wrapper")]
+#![cfg(all(feature = "verify", feature = "shake-128s"))]
 
 mod common;
 
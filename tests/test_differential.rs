@@ -0,0 +1,207 @@
+#![cfg(all(
+    feature = "sign",
+    any(
+        feature = "sha2-128s",
+        feature = "sha2-128f",
+        feature = "sha2-192s",
+        feature = "sha2-192f",
+        feature = "sha2-256s",
+        feature = "sha2-256f",
+        feature = "shake-128s",
+        feature = "shake-128f",
+        feature = "shake-192s",
+        feature = "shake-192f",
+        feature = "shake-256s",
+        feature = "shake-256f"
+    )
+))]
+
+//! Differential tests against the pure-Rust RustCrypto [`slh-dsa`] crate.
+//!
+//! For each parameter set, check that:
+//! - keys derived from the same seeds are identical;
+//! - deterministic signatures are byte-identical;
+//! - each implementation accepts the hedged signatures of the other one,
+//!   over random messages and contexts;
+//! - both implementations reject the same corrupted signatures.
+//!
+//! [`slh-dsa`]: https://crates.io/crates/slh-dsa
+
+mod common;
+
+use rand::RngExt;
+use slhdsa_c_rs::*;
+
+const ITERATIONS: usize = 3;
+
+/// The two implementations of the same parameter set
+struct Pair<P: ParameterSet, Q: slh_dsa::ParameterSet> {
+    sk: SigningKey<P>,
+    vk: VerifyingKey<P>,
+    their_sk: slh_dsa::SigningKey<Q>,
+    their_vk: slh_dsa::VerifyingKey<Q>,
+}
+
+fn random_bytes(rng: &mut impl RngExt, len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    rng.fill(&mut buf[..]);
+    buf
+}
+
+fn keypairs_from_seeds<P: ParameterSet, Q: slh_dsa::ParameterSet>(
+    rng: &mut impl RngExt,
+) -> Pair<P, Q> {
+    let n = P::VERIFYING_KEY_LEN / 2;
+    let sk_seed = random_bytes(rng, n);
+    let sk_prf = random_bytes(rng, n);
+    let pk_seed = random_bytes(rng, n);
+
+    let (sk, vk) = keygen_from_seeds::<P>(&sk_seed, &sk_prf, &pk_seed).expect("Keygen failed");
+    let their_sk = slh_dsa::SigningKey::<Q>::slh_keygen_internal(&sk_seed, &sk_prf, &pk_seed);
+    let their_vk = their_sk.verifying_key();
+
+    Pair {
+        sk,
+        vk,
+        their_sk,
+        their_vk,
+    }
+}
+
+fn random_msg_ctx(rng: &mut impl RngExt) -> (Vec<u8>, Vec<u8>) {
+    let msg_len = rng.random_range(0..1024);
+    let ctx_len = rng.random_range(0..=255);
+    (random_bytes(rng, msg_len), random_bytes(rng, ctx_len))
+}
+
+fn test_keygen_from_seeds<P: ParameterSet, Q: slh_dsa::ParameterSet>() {
+    let mut rng = rand::rng();
+
+    for _ in 0..ITERATIONS {
+        let pair = keypairs_from_seeds::<P, Q>(&mut rng);
+        assert_eq!(pair.sk.as_bytes(), &pair.their_sk.to_bytes()[..]);
+        assert_eq!(pair.vk.as_bytes(), &pair.their_vk.to_bytes()[..]);
+    }
+}
+
+fn test_deterministic_signatures<P: ParameterSet, Q: slh_dsa::ParameterSet>() {
+    let mut rng = rand::rng();
+    let pair = keypairs_from_seeds::<P, Q>(&mut rng);
+
+    for _ in 0..ITERATIONS {
+        let (msg, ctx) = random_msg_ctx(&mut rng);
+
+        let sig = pair
+            .sk
+            .try_sign_with_ctx(&msg, &ctx)
+            .expect("Signing failed");
+        let their_sig = pair
+            .their_sk
+            .try_sign_with_context(&msg, &ctx, None)
+            .expect("slh-dsa signing failed");
+
+        assert_eq!(sig.as_bytes(), &their_sig.to_bytes()[..]);
+    }
+}
+
+fn test_cross_verification<P: ParameterSet, Q: slh_dsa::ParameterSet>() {
+    let mut rng = rand::rng();
+    let pair = keypairs_from_seeds::<P, Q>(&mut rng);
+    let n = P::VERIFYING_KEY_LEN / 2;
+
+    for _ in 0..ITERATIONS {
+        let (msg, ctx) = random_msg_ctx(&mut rng);
+
+        // Theirs -> ours
+        let opt_rand = random_bytes(&mut rng, n);
+        let their_sig = pair
+            .their_sk
+            .try_sign_with_context(&msg, &ctx, Some(&opt_rand))
+            .expect("slh-dsa signing failed");
+        let sig = Signature::<P>::try_from(&their_sig.to_bytes()[..])
+            .expect("Failed to decode the slh-dsa signature");
+        pair.vk
+            .verify_with_ctx(&msg, &ctx, &sig)
+            .expect("slh-dsa signature rejected");
+
+        // Ours -> theirs
+        let sig = pair
+            .sk
+            .try_sign_hedged_with_ctx(&msg, &ctx)
+            .expect("Signing failed");
+        let their_sig = slh_dsa::Signature::<Q>::try_from(sig.as_bytes())
+            .expect("slh-dsa failed to decode the signature");
+        pair.their_vk
+            .try_verify_with_context(&msg, &ctx, &their_sig)
+            .expect("Signature rejected by slh-dsa");
+    }
+}
+
+fn test_cross_rejection<P: ParameterSet, Q: slh_dsa::ParameterSet>() {
+    let mut rng = rand::rng();
+    let pair = keypairs_from_seeds::<P, Q>(&mut rng);
+
+    for _ in 0..ITERATIONS {
+        let (msg, ctx) = random_msg_ctx(&mut rng);
+        let sig = pair
+            .sk
+            .try_sign_hedged_with_ctx(&msg, &ctx)
+            .expect("Signing failed");
+
+        let mut corrupted = sig.as_bytes().to_vec();
+        let i = rng.random_range(0..corrupted.len());
+        corrupted[i] ^= 1 << rng.random_range(0..8);
+
+        let sig = Signature::<P>::try_from(&corrupted[..]).unwrap();
+        let their_sig = slh_dsa::Signature::<Q>::try_from(&corrupted[..]).unwrap();
+
+        assert!(
+            pair.vk.verify_with_ctx(&msg, &ctx, &sig).is_err(),
+            "Corrupted signature accepted (byte {i})"
+        );
+        assert!(
+            pair.their_vk
+                .try_verify_with_context(&msg, &ctx, &their_sig)
+                .is_err(),
+            "Corrupted signature accepted by slh-dsa (byte {i})"
+        );
+    }
+}
+
+macro_rules! gen_differential_tests {
+    ( $( #[$meta:meta] $ty:ident => $theirs:ty ),+ $(,)? ) => {
+        $(
+            paste::paste! {
+                #[$meta]
+                #[test]
+                fn [<differential_keygen_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_keygen_from_seeds::<$ty, $theirs>();
+                }
+
+                #[$meta]
+                #[test]
+                fn [<differential_deterministic_sign_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_deterministic_signatures::<$ty, $theirs>();
+                }
+
+                #[$meta]
+                #[test]
+                fn [<differential_cross_verify_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_cross_verification::<$ty, $theirs>();
+                }
+
+                #[$meta]
+                #[test]
+                fn [<differential_cross_reject_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_cross_rejection::<$ty, $theirs>();
+                }
+            }
+        )+
+    };
+}
+
+slhdsa_c_rs::for_each_slh_dsa_pair!(gen_differential_tests);
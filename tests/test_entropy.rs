@@ -0,0 +1,160 @@
+#![cfg(all(feature = "sign", any(feature = "shake-128f", feature = "sha2-128f")))]
+
+//! The entropy source is process-global, so every test in this file
+//! serializes on `LOCK` and restores the default source before returning.
+
+mod common;
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use rand::{TryCryptoRng, TryRng};
+use slhdsa_c_rs::entropy::{self, EntropyError};
+use slhdsa_c_rs::*;
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Debug)]
+struct FailingRngError;
+
+impl core::fmt::Display for FailingRngError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Injected RNG failure")
+    }
+}
+
+impl core::error::Error for FailingRngError {}
+
+/// An RNG that always fails, simulating a broken OS entropy source.
+struct FailingRng;
+
+impl TryRng for FailingRng {
+    type Error = FailingRngError;
+
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        Err(FailingRngError)
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        Err(FailingRngError)
+    }
+
+    fn try_fill_bytes(&mut self, _dst: &mut [u8]) -> Result<(), Self::Error> {
+        Err(FailingRngError)
+    }
+}
+
+impl TryCryptoRng for FailingRng {}
+
+fn test_keygen_with_failing_rng<P: ParameterSet>() {
+    let _lock = lock();
+
+    entropy::set_rng(Box::leak(Box::new(FailingRng)));
+    let ret = keygen::<P>();
+    entropy::reset();
+
+    assert_eq!(
+        ret.unwrap_err(),
+        KeygenError::Entropy(EntropyError::SourceFailure)
+    );
+    assert!(keygen::<P>().is_ok());
+}
+
+fn test_keygen_with_failing_fill_fn<P: ParameterSet>() {
+    let _lock = lock();
+
+    entropy::set_fill_fn(|_| Err(EntropyError::Unavailable));
+    let ret = SigningKey::<P>::new();
+    entropy::reset();
+
+    assert_eq!(
+        ret.unwrap_err(),
+        KeygenError::Entropy(EntropyError::Unavailable)
+    );
+}
+
+fn test_hedged_sign_with_failing_rng<P: ParameterSet>() {
+    let _lock = lock();
+
+    let sk = SigningKey::<P>::new().expect("Keygen failed");
+    let msg = b"Hello, world!";
+
+    assert!(sk.try_sign_with_rng(&mut FailingRng, msg).is_err());
+
+    entropy::set_rng(Box::leak(Box::new(FailingRng)));
+    let ret = sk.try_sign_hedged_with_ctx(msg, b"");
+    entropy::reset();
+    assert!(ret.is_err());
+
+    // Deterministic signing does not need any entropy
+    entropy::set_rng(Box::leak(Box::new(FailingRng)));
+    let ret = sk.try_sign_with_ctx(msg, b"");
+    entropy::reset();
+    assert!(ret.is_ok());
+}
+
+/// The registry is not locked while the source runs, so it can replace
+/// itself, and its panics do not leave the registry locked
+fn test_keygen_with_reentrant_fill_fn<P: ParameterSet>() {
+    let _lock = lock();
+
+    entropy::set_fill_fn(|buf| {
+        entropy::reset();
+        entropy::fill(buf)
+    });
+    let ret = keygen::<P>();
+    entropy::reset();
+    assert!(ret.is_ok());
+
+    entropy::set_fill_fn(|_| panic!("Injected entropy source panic"));
+    let ret = std::panic::catch_unwind(keygen::<P>);
+    entropy::reset();
+    assert!(ret.is_err());
+    assert!(keygen::<P>().is_ok());
+}
+
+macro_rules! gen_entropy_tests {
+    ( $( $(#[$meta:meta])* $ty:ident ),+ $(,)? ) => {
+        $(
+            paste::paste! {
+                $(#[$meta])*
+                #[test]
+                fn [<keygen_with_failing_rng_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_keygen_with_failing_rng::<$ty>();
+                }
+
+                $(#[$meta])*
+                #[test]
+                fn [<keygen_with_failing_fill_fn_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_keygen_with_failing_fill_fn::<$ty>();
+                }
+
+                $(#[$meta])*
+                #[test]
+                fn [<hedged_sign_with_failing_rng_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_hedged_sign_with_failing_rng::<$ty>();
+                }
+
+                $(#[$meta])*
+                #[test]
+                fn [<keygen_with_reentrant_fill_fn_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_keygen_with_reentrant_fill_fn::<$ty>();
+                }
+            }
+        )+
+    };
+}
+
+gen_entropy_tests!(
+    #[cfg(feature = "shake-128f")]
+    SLH_DSA_SHAKE_128f,
+    #[cfg(feature = "sha2-128f")]
+    SLH_DSA_SHA2_128f,
+);
@@ -0,0 +1,222 @@
+#![cfg(all(
+    feature = "kat",
+    any(
+        feature = "sha2-128s",
+        feature = "sha2-128f",
+        feature = "sha2-192s",
+        feature = "sha2-192f",
+        feature = "sha2-256s",
+        feature = "sha2-256f",
+        feature = "shake-128s",
+        feature = "shake-128f",
+        feature = "shake-192s",
+        feature = "shake-192f",
+        feature = "shake-256s",
+        feature = "shake-256f"
+    )
+))]
+
+//! Replay the NIST-style SLH-DSA known-answer test files.
+//!
+//! For each parameter set, the harness looks for `<NAME>.rsp` (e.g.,
+//! `SLH-DSA-SHAKE-128s.rsp`) in the directory pointed to by the
+//! `SLHDSA_KAT_DIR` environment variable, or in `tests/kat/` by default.
+//! The full response files are too large to be shipped with the crate (see
+//! `tests/kat/README.md`), so these tests are ignored by default: run them
+//! with `--ignored`, where a missing file fails the test.
+//!
+//! Cross-implementation response files, with a single entry computed by the
+//! RustCrypto `slh-dsa` crate, are shipped in `tests/kat/cross/` for every
+//! parameter set, and always replayed.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use slhdsa_c_rs::kat::{KatDrbg, SEED_LEN};
+use slhdsa_c_rs::*;
+
+/// A single `count = ...` entry of a `.rsp` file
+#[derive(Debug, Default)]
+struct KatEntry {
+    count: usize,
+    seed: Vec<u8>,
+    msg: Vec<u8>,
+    ctx: Vec<u8>,
+    pk: Vec<u8>,
+    sk: Vec<u8>,
+    sm: Vec<u8>,
+}
+
+fn kat_dir() -> PathBuf {
+    match std::env::var_os("SLHDSA_KAT_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("kat"),
+    }
+}
+
+fn cross_kat_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("kat")
+        .join("cross")
+}
+
+fn parse_rsp(content: &str) -> Vec<KatEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<KatEntry> = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+
+        if key == "count" {
+            if let Some(entry) = current.take() {
+                entries.push(entry);
+            }
+            current = Some(KatEntry {
+                count: value.parse().expect("Invalid count"),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let entry = current.as_mut().expect("Field before the first count");
+        let decode = |v: &str| hex::decode(v).expect("Invalid hex");
+        match key {
+            "seed" => entry.seed = decode(value),
+            "msg" => entry.msg = decode(value),
+            "ctx" => entry.ctx = decode(value),
+            "pk" => entry.pk = decode(value),
+            "sk" => entry.sk = decode(value),
+            "sm" => entry.sm = decode(value),
+            // mlen, smlen, ctxlen are implied by the hex values
+            _ => (),
+        }
+    }
+    entries.extend(current);
+
+    entries
+}
+
+fn test_kat<P: ParameterSet>(dir: &Path) {
+    let path = dir.join(format!("{}.rsp", P::NAME));
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Cannot read {}: {e}", path.display()));
+
+    let entries = parse_rsp(&content);
+    assert!(!entries.is_empty(), "No entries in {}", path.display());
+
+    for entry in entries {
+        log::info!("{} count={}", P::NAME, entry.count);
+
+        let seed: &[u8; SEED_LEN] = entry.seed.as_slice().try_into().expect("Invalid seed");
+        let mut drbg = KatDrbg::new(seed, None);
+
+        let (sk, vk) = keygen_with_rng::<P, _>(&mut drbg).expect("Keygen failed");
+        assert_eq!(
+            vk.as_bytes(),
+            entry.pk,
+            "pk mismatch at count={}",
+            entry.count
+        );
+        assert_eq!(
+            sk.as_bytes(),
+            entry.sk,
+            "sk mismatch at count={}",
+            entry.count
+        );
+
+        let sig = sk
+            .try_sign_with_ctx_and_rng(&entry.msg, &entry.ctx, &mut drbg)
+            .expect("Signing failed");
+        let sm = [sig.as_bytes(), &entry.msg].concat();
+        assert_eq!(sm, entry.sm, "sm mismatch at count={}", entry.count);
+
+        vk.verify_with_ctx(&entry.msg, &entry.ctx, &sig)
+            .expect("Verification of the KAT signature failed");
+    }
+}
+
+macro_rules! gen_kat_tests {
+    ( $( $(#[$meta:meta])* $ty:ident ),+ $(,)? ) => {
+        $(
+            paste::paste! {
+                $(#[$meta])*
+                #[test]
+                #[ignore = "needs the KAT response files, see tests/kat/README.md"]
+                fn [<kat_test_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_kat::<$ty>(&kat_dir());
+                }
+
+                $(#[$meta])*
+                #[test]
+                fn [<kat_cross_test_ $ty:lower>]() {
+                    common::setup().expect("Failed during initial setup");
+                    test_kat::<$ty>(&cross_kat_dir());
+                }
+            }
+        )+
+    };
+}
+
+gen_kat_tests!(
+    #[cfg(feature = "shake-128s")]
+    SLH_DSA_SHAKE_128s,
+    #[cfg(feature = "shake-128f")]
+    SLH_DSA_SHAKE_128f,
+    #[cfg(feature = "shake-192s")]
+    SLH_DSA_SHAKE_192s,
+    #[cfg(feature = "shake-192f")]
+    SLH_DSA_SHAKE_192f,
+    #[cfg(feature = "shake-256s")]
+    SLH_DSA_SHAKE_256s,
+    #[cfg(feature = "shake-256f")]
+    SLH_DSA_SHAKE_256f,
+    #[cfg(feature = "sha2-128s")]
+    SLH_DSA_SHA2_128s,
+    #[cfg(feature = "sha2-128f")]
+    SLH_DSA_SHA2_128f,
+    #[cfg(feature = "sha2-192s")]
+    SLH_DSA_SHA2_192s,
+    #[cfg(feature = "sha2-192f")]
+    SLH_DSA_SHA2_192f,
+    #[cfg(feature = "sha2-256s")]
+    SLH_DSA_SHA2_256s,
+    #[cfg(feature = "sha2-256f")]
+    SLH_DSA_SHA2_256f,
+);
+
+#[test]
+fn parse_rsp_entries() {
+    let content = "\
+# SLH-DSA-SHAKE-128s
+
+count = 0
+seed = 00
+mlen = 1
+msg = AA
+
+count = 1
+seed = 01
+mlen = 2
+msg = BBCC
+ctx = DD
+";
+    let entries = parse_rsp(content);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].count, 0);
+    assert_eq!(entries[0].msg, [0xAA]);
+    assert!(entries[0].ctx.is_empty());
+    assert_eq!(entries[1].count, 1);
+    assert_eq!(entries[1].msg, [0xBB, 0xCC]);
+    assert_eq!(entries[1].ctx, [0xDD]);
+}